@@ -1,32 +1,158 @@
-use async_graphql::{Context, Error, Result};
+use std::env;
+
+use async_graphql::{
+    parser::{
+        self,
+        types::{OperationType, Selection},
+    },
+    Context, Error, Result,
+};
 use axum::http::HeaderMap;
 use bson::Uuid;
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 /// `Authorized-User` HTTP header.
-#[derive(Deserialize, Debug, Serialize)]
+#[derive(Deserialize, Debug, Serialize, PartialEq)]
 pub struct AuthorizedUserHeader {
-    id: Uuid,
+    pub id: Uuid,
     roles: Vec<Role>,
 }
 
+/// Default name of the `Authorized-User` HTTP header.
+const DEFAULT_AUTHORIZED_USER_HEADER_NAME: &str = "Authorized-User";
+
+/// Returns the name of the `Authorized-User` HTTP header of incoming requests.
+///
+/// Read from `$AUTHORIZED_USER_HEADER_NAME`, defaults to `DEFAULT_AUTHORIZED_USER_HEADER_NAME`.
+pub fn authorized_user_header_name() -> String {
+    env::var("AUTHORIZED_USER_HEADER_NAME")
+        .unwrap_or(DEFAULT_AUTHORIZED_USER_HEADER_NAME.to_string())
+}
+
+/// Defines if requests without a valid `Authorized-User` header are rejected before reaching the resolvers.
+///
+/// Otherwise such requests are passed on and each resolver checks authorization on its own.
+/// Requests only selecting public root query fields (see `is_public_request`) are always passed on.
+/// Read from `$AUTH_REQUIRED`, defaults to `false`.
+pub fn authentication_required() -> bool {
+    env::var("AUTH_REQUIRED")
+        .ok()
+        .and_then(|required| required.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Root query fields which are reachable without a valid `Authorized-User` header even if `$AUTH_REQUIRED` is set.
+///
+/// `publicOrderSummary` does not require authorization, `_service` serves the federation SDL to the gateway and `_entities` authorizes in each entity resolver.
+const PUBLIC_ROOT_QUERY_FIELDS: [&str; 4] =
+    ["publicOrderSummary", "_service", "_entities", "__typename"];
+
+/// Defines if a GraphQL request only selects root query fields of `PUBLIC_ROOT_QUERY_FIELDS`.
+///
+/// Such requests are exempt from `authentication_required`.
+/// Requests which can not be parsed, contain mutations or select root fields through fragments are not public.
+///
+/// * `query` - GraphQL query document of the request.
+pub fn is_public_request(query: &str) -> bool {
+    let Ok(document) = parser::parse_query(query) else {
+        return false;
+    };
+    document.operations.iter().all(|(_, operation)| {
+        operation.node.ty == OperationType::Query
+            && operation
+                .node
+                .selection_set
+                .node
+                .items
+                .iter()
+                .all(|selection| match &selection.node {
+                    Selection::Field(field) => {
+                        PUBLIC_ROOT_QUERY_FIELDS.contains(&field.node.name.node.as_str())
+                    }
+                    _ => false,
+                })
+    })
+}
+
+impl AuthorizedUserHeader {
+    /// Extracts the `Authorized-User` header, named according to `authorized_user_header_name`, from a header map.
+    ///
+    /// Returns `None` if the header is not set and an error if it is set but malformed.
+    /// The header value is trimmed before parsing, a malformed header is logged as a warning.
+    ///
+    /// * `header_map` - Header map to extract the `Authorized-User` header from.
+    pub fn from_header_map(header_map: &HeaderMap) -> Result<Option<Self>> {
+        let Some(authorized_user_header_value) =
+            header_map.get(authorized_user_header_name().as_str())
+        else {
+            return Ok(None);
+        };
+        let authorized_user_header = authorized_user_header_value
+            .to_str()
+            .map_err(|error| error.to_string())
+            .and_then(|authorized_user_header_str| {
+                parse_authorized_user_header(authorized_user_header_str.trim())
+            });
+        match authorized_user_header {
+            Ok(authorized_user_header) => Ok(Some(authorized_user_header)),
+            Err(error) => {
+                warn!("Authorized-User header is malformed: {}", error);
+                Err(Error::new(
+                    "Authorization failed. Authorized-User header could not be parsed.",
+                ))
+            }
+        }
+    }
+
+    /// Serializes the header to forward it to other services.
+    ///
+    /// Contains the attributes of the received header in their normalized form, unknown attributes are dropped.
+    pub fn to_forwarded_header_value(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Attributes of the `Authorized-User` header, which are forwarded to other services.
+const AUTHORIZED_USER_HEADER_ATTRIBUTES: [&str; 2] = ["id", "roles"];
+
+/// Parses the JSON value of an `Authorized-User` header.
+///
+/// Only headers which are not valid JSON or whose attributes can not be parsed are rejected.
+/// Differently formatted attributes, e.g. uppercase UUIDs, are accepted and forwarded to other services in their normalized form.
+/// Unknown attributes are not forwarded, which is logged as a warning.
+///
+/// * `authorized_user_header_str` - JSON value of the `Authorized-User` header.
+fn parse_authorized_user_header(
+    authorized_user_header_str: &str,
+) -> Result<AuthorizedUserHeader, String> {
+    let authorized_user_header: AuthorizedUserHeader =
+        serde_json::from_str(authorized_user_header_str).map_err(|error| error.to_string())?;
+    let received_json: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(authorized_user_header_str).map_err(|error| error.to_string())?;
+    let unknown_attributes: Vec<&String> = received_json
+        .keys()
+        .filter(|key| !AUTHORIZED_USER_HEADER_ATTRIBUTES.contains(&key.as_str()))
+        .collect();
+    if !unknown_attributes.is_empty() {
+        warn!(
+            "Authorized-User header contains unknown attributes: `{:?}`, which are not forwarded.",
+            unknown_attributes
+        );
+    }
+    Ok(authorized_user_header)
+}
+
 /// Extraction of `Authorized-User` header from header map.
 impl TryFrom<&HeaderMap> for AuthorizedUserHeader {
     type Error = Error;
 
-    /// Tries to extract the `Authorized-User` header from a header map.
+    /// Tries to extract the `Authorized-User` header with `AuthorizedUserHeader::from_header_map`.
     ///
-    /// Returns a GraphQL error if the extraction fails.
+    /// Returns a GraphQL error if the header is not set or malformed.
     fn try_from(header_map: &HeaderMap) -> Result<Self, Self::Error> {
-        if let Some(authorized_user_header_value) = header_map.get("Authorized-User") {
-            if let Ok(authorized_user_header_str) = authorized_user_header_value.to_str() {
-                let authorized_user_header: AuthorizedUserHeader =
-                    serde_json::from_str(authorized_user_header_str)?;
-                return Ok(authorized_user_header);
-            }
-        }
-        Err(Error::new(
-            "Authorization failed. Authorized-User header is not set or could not be parsed.",
+        AuthorizedUserHeader::from_header_map(header_map)?.ok_or(Error::new(
+            "Authorization failed. Authorized-User header is not set.",
         ))
     }
 }
@@ -38,6 +164,7 @@ enum Role {
     Buyer,
     Admin,
     Employee,
+    Service,
 }
 
 impl Role {
@@ -47,6 +174,7 @@ impl Role {
             Self::Buyer => false,
             Self::Admin => true,
             Self::Employee => true,
+            Self::Service => false,
         }
     }
 }
@@ -64,6 +192,59 @@ pub fn authorize_user(ctx: &Context, id: Option<Uuid>) -> Result<()> {
     }
 }
 
+/// Authorize trusted internal service for a context.
+///
+/// Permission is valid only if the `Authorized-User` header contains `Role::Service`.
+///
+/// * `context` - GraphQL context containing the `Authorized-User` header.
+pub fn authorize_service(ctx: &Context) -> Result<()> {
+    let authorized_user_header = ctx.data::<AuthorizedUserHeader>().map_err(|_| {
+        Error::new(
+            "Authentication failed. Authorized-User header is not set or could not be parsed.",
+        )
+    })?;
+    match authorized_user_header.roles.contains(&Role::Service) {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "Authentication failed for user of UUID: `{}`. Operation is restricted to services.",
+                authorized_user_header.id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Authorize admin for a context.
+///
+/// Permission is valid only if the `Authorized-User` header contains `Role::Admin`, other permissive roles are not sufficient.
+///
+/// * `context` - GraphQL context containing the `Authorized-User` header.
+pub fn authorize_admin(ctx: &Context) -> Result<()> {
+    let authorized_user_header = ctx.data::<AuthorizedUserHeader>().map_err(|_| {
+        Error::new(
+            "Authentication failed. Authorized-User header is not set or could not be parsed.",
+        )
+    })?;
+    check_admin_permissions(authorized_user_header)
+}
+
+/// Check if the user has `Role::Admin` according to the `Authorized-User` header.
+///
+/// * `authorized_user_header` - `Authorized-User` header containing the users UUID and role.
+fn check_admin_permissions(authorized_user_header: &AuthorizedUserHeader) -> Result<()> {
+    match authorized_user_header.roles.contains(&Role::Admin) {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "Authentication failed for user of UUID: `{}`. Operation is restricted to admins.",
+                authorized_user_header.id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
 /// Check if user of UUID has a valid permission according to the `Authorized-User` header.
 ///
 /// Permission is valid if the user has `Role::Buyer` and the same UUID as provided in the function parameter.
@@ -93,3 +274,130 @@ pub fn check_permissions(
         return Err(Error::new(message));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_ID: &str = "3a4f1bd6-0bcb-4f5c-9a43-5b0e7b36c0f5";
+
+    /// Builds a header map containing an `Authorized-User` header.
+    fn build_header_map(authorized_user_header_str: &str) -> HeaderMap {
+        let mut header_map = HeaderMap::new();
+        header_map.insert(
+            DEFAULT_AUTHORIZED_USER_HEADER_NAME,
+            authorized_user_header_str.parse().unwrap(),
+        );
+        header_map
+    }
+
+    #[test]
+    fn valid_authorized_user_header_is_forwarded_unchanged() {
+        let authorized_user_header_str =
+            format!(r#"{{"id":"{}","roles":["buyer","admin"]}}"#, USER_ID);
+        let header_map = build_header_map(&format!(" {} ", authorized_user_header_str));
+        let authorized_user_header = AuthorizedUserHeader::from_header_map(&header_map)
+            .unwrap()
+            .unwrap();
+        assert_eq!(authorized_user_header.id, Uuid::parse_str(USER_ID).unwrap());
+        assert_eq!(authorized_user_header.roles, vec![Role::Buyer, Role::Admin]);
+        assert_eq!(
+            authorized_user_header.to_forwarded_header_value().unwrap(),
+            authorized_user_header_str
+        );
+    }
+
+    #[test]
+    fn unknown_attributes_of_authorized_user_header_are_dropped() {
+        let header_map = build_header_map(&format!(
+            r#"{{"id":"{}","roles":["buyer"],"name":"Erika"}}"#,
+            USER_ID
+        ));
+        let authorized_user_header = AuthorizedUserHeader::from_header_map(&header_map)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            authorized_user_header.to_forwarded_header_value().unwrap(),
+            format!(r#"{{"id":"{}","roles":["buyer"]}}"#, USER_ID)
+        );
+    }
+
+    #[test]
+    fn uppercase_uuid_of_authorized_user_header_is_forwarded_normalized() {
+        let header_map = build_header_map(&format!(
+            r#"{{"id":"{}","roles":["buyer"]}}"#,
+            USER_ID.to_uppercase()
+        ));
+        let authorized_user_header = AuthorizedUserHeader::from_header_map(&header_map)
+            .unwrap()
+            .unwrap();
+        assert_eq!(authorized_user_header.id, Uuid::parse_str(USER_ID).unwrap());
+        assert_eq!(
+            authorized_user_header.to_forwarded_header_value().unwrap(),
+            format!(r#"{{"id":"{}","roles":["buyer"]}}"#, USER_ID)
+        );
+    }
+
+    #[test]
+    fn malformed_authorized_user_header_is_rejected() {
+        for authorized_user_header_str in [
+            "not json".to_string(),
+            format!(r#"{{"id":"{}"}}"#, USER_ID),
+            format!(r#"{{"id":"{}","roles":["owner"]}}"#, USER_ID),
+            format!(r#"["{}"]"#, USER_ID),
+        ] {
+            let header_map = build_header_map(&authorized_user_header_str);
+            assert!(
+                AuthorizedUserHeader::from_header_map(&header_map).is_err(),
+                "{}",
+                authorized_user_header_str
+            );
+            assert!(AuthorizedUserHeader::try_from(&header_map).is_err());
+        }
+    }
+
+    #[test]
+    fn missing_authorized_user_header_is_none() {
+        let header_map = HeaderMap::new();
+        assert!(AuthorizedUserHeader::from_header_map(&header_map)
+            .unwrap()
+            .is_none());
+        assert!(AuthorizedUserHeader::try_from(&header_map).is_err());
+    }
+
+    #[test]
+    fn public_order_summary_and_federation_requests_are_public() {
+        assert!(is_public_request(
+            "{ publicOrderSummary(id: \"3a4f1bd6-0bcb-4f5c-9a43-5b0e7b36c0f5\") { id } }"
+        ));
+        assert!(is_public_request("query { _service { sdl } }"));
+        assert!(is_public_request(
+            "query($representations: [_Any!]!) { _entities(representations: $representations) { __typename } }"
+        ));
+    }
+
+    #[test]
+    fn only_admins_have_admin_permissions() {
+        let build_header = |roles: Vec<Role>| AuthorizedUserHeader {
+            id: Uuid::new(),
+            roles,
+        };
+        assert!(check_admin_permissions(&build_header(vec![Role::Admin])).is_ok());
+        assert!(check_admin_permissions(&build_header(vec![Role::Buyer, Role::Admin])).is_ok());
+        assert!(check_admin_permissions(&build_header(vec![Role::Employee])).is_err());
+        assert!(check_admin_permissions(&build_header(vec![Role::Service])).is_err());
+        assert!(check_permissions(&build_header(vec![Role::Employee]), None).is_ok());
+    }
+
+    #[test]
+    fn requests_selecting_other_fields_are_not_public() {
+        assert!(!is_public_request(
+            "{ publicOrderSummary(id: \"3a4f1bd6-0bcb-4f5c-9a43-5b0e7b36c0f5\") { id } orders { totalCount } }"
+        ));
+        assert!(!is_public_request("mutation { _service { sdl } }"));
+        assert!(!is_public_request(
+            "query { ...Public } fragment Public on Query { _service { sdl } }"
+        ));
+        assert!(!is_public_request("query { _service { sdl }"));
+    }
+}
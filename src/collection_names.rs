@@ -0,0 +1,22 @@
+//! Names of the MongoDB collections used by the order service.
+
+/// Collection of orders.
+pub const ORDERS: &str = "orders";
+/// Collection of order items.
+pub const ORDER_ITEMS: &str = "order_items";
+/// Collection of order compensations.
+pub const ORDER_COMPENSATIONS: &str = "order_compensations";
+/// Collection of users.
+pub const USERS: &str = "users";
+/// Collection of product variants.
+pub const PRODUCT_VARIANTS: &str = "product_variants";
+/// Collection of all product variant versions, including versions which are no longer current.
+pub const PRODUCT_VARIANT_VERSIONS: &str = "product_variant_versions";
+/// Collection of coupons.
+pub const COUPONS: &str = "coupons";
+/// Collection of tax rates.
+pub const TAX_RATES: &str = "tax_rates";
+/// Collection of shipment methods.
+pub const SHIPMENT_METHODS: &str = "shipment_methods";
+/// Collection of payment informations.
+pub const PAYMENT_INFORMATIONS: &str = "payment_informations";
@@ -0,0 +1,26 @@
+use axum::http::HeaderMap;
+use bson::Uuid;
+
+/// Name of the HTTP header containing the correlation id.
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// Correlation id of a request, which is forwarded to all downstream calls.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrelationId(pub String);
+
+/// Extraction of `X-Correlation-Id` header from header map.
+impl From<&HeaderMap> for CorrelationId {
+    /// Extracts the `X-Correlation-Id` header from a header map.
+    ///
+    /// Generates a new correlation id if the header is not set or could not be parsed.
+    fn from(header_map: &HeaderMap) -> Self {
+        let maybe_correlation_id = header_map
+            .get(CORRELATION_ID_HEADER)
+            .and_then(|correlation_id_header_value| correlation_id_header_value.to_str().ok())
+            .filter(|correlation_id| !correlation_id.is_empty());
+        match maybe_correlation_id {
+            Some(correlation_id) => Self(correlation_id.to_string()),
+            None => Self(Uuid::new().to_string()),
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! Addressing of the Dapr sidecar, which the order service invokes other services and publishes events through.
+
+use std::env;
+
+/// Default HTTP port of the Dapr sidecar.
+const DEFAULT_DAPR_HTTP_PORT: u16 = 3500;
+
+/// Builds the URL of an endpoint of the Dapr sidecar.
+///
+/// The port is read from `$DAPR_HTTP_PORT`, which the Dapr sidecar sets for its application, and defaults to `DEFAULT_DAPR_HTTP_PORT`.
+///
+/// * `path` - Path of the endpoint, e.g. `v1.0/invoke/inventory/method/graphql`.
+pub fn dapr_url(path: &str) -> String {
+    let port = env::var("DAPR_HTTP_PORT")
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_DAPR_HTTP_PORT);
+    format!("http://localhost:{}/{}", port, path)
+}
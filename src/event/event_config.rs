@@ -0,0 +1,43 @@
+use std::env;
+
+use crate::dapr::dapr_url;
+
+/// Default name of the Dapr pub/sub component.
+const DEFAULT_PUBSUB_NAME: &str = "pubsub";
+/// Default topic of the order created event.
+const DEFAULT_ORDER_CREATED_TOPIC: &str = "order/order/created";
+/// Default topic of the order compensation created event.
+const DEFAULT_ORDER_COMPENSATION_CREATED_TOPIC: &str = "order/order-compensation/created";
+
+/// Configuration of the events published by the order service.
+#[derive(Debug, Clone)]
+pub struct EventConfig {
+    /// Name of the Dapr pub/sub component to publish events with.
+    pub pubsub_name: String,
+    /// Topic of the order created event.
+    pub order_created_topic: String,
+    /// Topic of the order compensation created event.
+    pub order_compensation_created_topic: String,
+}
+
+impl EventConfig {
+    /// Reads the event configuration from `$PUBSUB_NAME`, `$ORDER_CREATED_TOPIC` and `$ORDER_COMPENSATION_CREATED_TOPIC`.
+    ///
+    /// Defaults to the topics and pub/sub component name the order service used before they were configurable.
+    pub fn from_env() -> Self {
+        Self {
+            pubsub_name: env::var("PUBSUB_NAME").unwrap_or(DEFAULT_PUBSUB_NAME.to_string()),
+            order_created_topic: env::var("ORDER_CREATED_TOPIC")
+                .unwrap_or(DEFAULT_ORDER_CREATED_TOPIC.to_string()),
+            order_compensation_created_topic: env::var("ORDER_COMPENSATION_CREATED_TOPIC")
+                .unwrap_or(DEFAULT_ORDER_COMPENSATION_CREATED_TOPIC.to_string()),
+        }
+    }
+
+    /// Builds the Dapr publish URL of a topic.
+    ///
+    /// * `topic` - Topic to publish to.
+    pub fn publish_url(&self, topic: &str) -> String {
+        dapr_url(&format!("v1.0/publish/{}/{}", self.pubsub_name, topic))
+    }
+}
@@ -1,15 +1,29 @@
-use axum::{debug_handler, extract::State, http::StatusCode, Json};
-use bson::{doc, Uuid};
-use log::info;
+use std::env;
+
+use axum::{
+    async_trait, debug_handler,
+    extract::{rejection::JsonRejection, FromRequest, State},
+    http::{Request, StatusCode},
+    routing::{post, MethodRouter},
+    Json,
+};
+use bson::{doc, Document, Uuid};
 use mongodb::{options::UpdateOptions, Collection};
 use serde::{Deserialize, Serialize};
+use tracing::{info, instrument, warn};
 
 use crate::{
-    event::order_compensation::compensate_order,
+    event::{
+        event_config::EventConfig,
+        order_compensation::{
+            compensate_order, is_compensation_not_applicable, CompensationSource,
+        },
+    },
     graphql::{
         model::{
             foreign_types::{
-                Coupon, ProductVariant, ProductVariantVersion, ShipmentMethod, TaxRate,
+                Coupon, PaymentInformation, ProductVariant, ProductVariantVersion, ShipmentMethod,
+                TaxRate,
             },
             order::Order,
             user::User,
@@ -42,6 +56,69 @@ impl Default for TopicEventResponse {
     }
 }
 
+/// Status code for events which can never be processed, e.g. malformed payloads or unknown topics.
+///
+/// Dapr drops events answered with `404 Not Found`, while events answered with any other error status are redelivered.
+/// Transient failures, e.g. database errors, are answered with `500 Internal Server Error` to trigger redelivery.
+pub const UNPROCESSABLE_EVENT_STATUS_CODE: StatusCode = StatusCode::NOT_FOUND;
+
+/// JSON extractor for Dapr events, which rejects malformed payloads with `UNPROCESSABLE_EVENT_STATUS_CODE`.
+///
+/// The default `Json` extractor rejects malformed payloads with client error statuses, which Dapr redelivers indefinitely.
+pub struct EventJson<T>(pub T);
+
+#[async_trait]
+impl<T, S, B> FromRequest<S, B> for EventJson<T>
+where
+    Json<T>: FromRequest<S, B, Rejection = JsonRejection>,
+    S: Send + Sync,
+    B: Send + 'static,
+{
+    type Rejection = StatusCode;
+
+    async fn from_request(req: Request<B>, state: &S) -> Result<Self, Self::Rejection> {
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(Self(value)),
+            Err(rejection) => {
+                warn!("Event payload is unprocessable and dropped: {}", rejection);
+                Err(UNPROCESSABLE_EVENT_STATUS_CODE)
+            }
+        }
+    }
+}
+
+/// Logs an event of a topic the event handler does not handle and returns `UNPROCESSABLE_EVENT_STATUS_CODE`.
+///
+/// * `topic` - Topic of the unhandled event.
+fn reject_unknown_topic(topic: &str) -> StatusCode {
+    warn!("Event of unknown topic: `{}` is dropped.", topic);
+    UNPROCESSABLE_EVENT_STATUS_CODE
+}
+
+/// Maps an error of `compensate_order` to the status code of the event response.
+///
+/// Events causing compensations which can never be applied are dropped with `UNPROCESSABLE_EVENT_STATUS_CODE`, transient failures are redelivered.
+///
+/// * `error` - Error returned by `compensate_order`.
+fn map_compensation_error(error: async_graphql::Error) -> StatusCode {
+    match is_compensation_not_applicable(&error) {
+        true => {
+            warn!(
+                "Event is dropped, compensation is not applicable: {}",
+                error.message
+            );
+            UNPROCESSABLE_EVENT_STATUS_CODE
+        }
+        false => {
+            warn!(
+                "Compensation failed, event is redelivered: {}",
+                error.message
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
 /// Relevant part of Dapr event wrapped in a cloud envelope.
 #[derive(Deserialize, Debug)]
 pub struct Event<T> {
@@ -58,7 +135,7 @@ pub struct UuidEventData {
 /// Event data containing a product variant version.
 ///
 /// Differs from product variant version in the `id` field naming.
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductVariantVersionEventData {
     /// UUID of product variant version.
@@ -93,6 +170,34 @@ pub struct UserAddressEventData {
     pub user_id: Uuid,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentMethodEventData {
+    /// UUID of the shipment method.
+    pub id: Uuid,
+    /// Estimated amount of days the shipment method requires for delivery.
+    #[serde(default)]
+    pub estimated_delivery_days: Option<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ShipmentMethodUpdatedEventData {
+    /// UUID of the shipment method to update.
+    pub id: Uuid,
+    /// Defines if shipment method can be selected for new orders.
+    pub enabled: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentInformationEventData {
+    /// UUID of the payment information.
+    pub id: Uuid,
+    /// UUID of the user owning the payment information.
+    pub user_id: Uuid,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ShipmentFailedEventData {
@@ -102,6 +207,13 @@ pub struct ShipmentFailedEventData {
     pub order_item_ids: Vec<Uuid>,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentFailedEventData {
+    /// UUID of the order of payment.
+    pub order_id: Uuid,
+}
+
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ShipmentStatusUpdatedEventData {
@@ -135,78 +247,153 @@ pub struct UpdateProductVariantEventData {
 #[derive(Clone)]
 pub struct HttpEventServiceState {
     pub product_variant_collection: Collection<ProductVariant>,
+    pub product_variant_version_collection: Collection<ProductVariantVersion>,
     pub coupon_collection: Collection<Coupon>,
     pub tax_rate_collection: Collection<TaxRate>,
     pub shipment_method_collection: Collection<ShipmentMethod>,
     pub user_collection: Collection<User>,
+    pub payment_information_collection: Collection<PaymentInformation>,
     pub order_collection: Collection<Order>,
     pub order_compensation_collection: Collection<OrderCompensation>,
+    pub event_config: EventConfig,
+}
+
+/// Topics the service subscribes to and the routes of their event handlers.
+///
+/// Single source of truth for the subscriptions listed to Dapr, the routes are verified against the registered event handlers by `verify_topic_subscription_routes`.
+pub const TOPIC_SUBSCRIPTIONS: [(&str, &str); 13] = [
+    (
+        "catalog/product-variant/updated",
+        "/on-product-variant-updated-event",
+    ),
+    (
+        "catalog/product-variant-version/created",
+        "/on-product-variant-version-creation-event",
+    ),
+    ("discount/coupon/created", "/on-id-creation-event"),
+    (
+        "tax/tax-rate-version/created",
+        "/on-tax-rate-version-creation-event",
+    ),
+    ("tax/tax-rate/deleted", "/on-tax-rate-deleted-event"),
+    (
+        "shipment/shipment-method/created",
+        "/on-shipment-method-creation-event",
+    ),
+    (
+        "shipment/shipment-method/updated",
+        "/on-shipment-method-updated-event",
+    ),
+    (
+        "shipment/shipment/creation-failed",
+        "/on-shipment-creation-failed-event",
+    ),
+    ("user/user/created", "/on-id-creation-event"),
+    (
+        "payment/payment-information/created",
+        "/on-payment-information-creation-event",
+    ),
+    ("payment/payment/failed", "/on-payment-failed-event"),
+    (
+        "address/user-address/created",
+        "/on-user-address-creation-event",
+    ),
+    (
+        "address/user-address/archived",
+        "/on-user-address-archived-event",
+    ),
+];
+
+/// Returns the routes of all event handlers together with their handlers.
+pub fn event_handler_routes() -> Vec<(&'static str, MethodRouter<HttpEventServiceState>)> {
+    vec![
+        ("/on-id-creation-event", post(on_id_creation_event)),
+        (
+            "/on-product-variant-version-creation-event",
+            post(on_product_variant_version_creation_event),
+        ),
+        (
+            "/on-product-variant-updated-event",
+            post(on_product_variant_update_event),
+        ),
+        (
+            "/on-shipment-method-creation-event",
+            post(on_shipment_method_creation_event),
+        ),
+        (
+            "/on-shipment-method-updated-event",
+            post(on_shipment_method_updated_event),
+        ),
+        (
+            "/on-tax-rate-version-creation-event",
+            post(on_tax_rate_version_creation_event),
+        ),
+        (
+            "/on-tax-rate-deleted-event",
+            post(on_tax_rate_deleted_event),
+        ),
+        (
+            "/on-payment-information-creation-event",
+            post(on_payment_information_creation_event),
+        ),
+        (
+            "/on-user-address-creation-event",
+            post(on_user_address_creation_event),
+        ),
+        (
+            "/on-user-address-archived-event",
+            post(on_user_address_archived_event),
+        ),
+        (
+            "/on-shipment-creation-failed-event",
+            post(on_shipment_creation_failed_event),
+        ),
+        ("/on-payment-failed-event", post(on_payment_failed_event)),
+    ]
+}
+
+/// Verifies that the route of each topic subscription has a registered event handler, panics otherwise.
+///
+/// Events of topics without event handler would silently fail to be delivered, the service is therefore not started.
+///
+/// * `registered_routes` - Routes of the registered event handlers.
+pub fn verify_topic_subscription_routes(registered_routes: &[&str]) {
+    let unregistered_routes: Vec<&str> = TOPIC_SUBSCRIPTIONS
+        .iter()
+        .map(|(_, route)| *route)
+        .filter(|route| !registered_routes.contains(route))
+        .collect();
+    if !unregistered_routes.is_empty() {
+        panic!(
+            "Topic subscriptions with routes: `{:?}` have no registered event handler.",
+            unregistered_routes
+        );
+    }
 }
 
 /// HTTP endpoint to list topic subsciptions.
 pub async fn list_topic_subscriptions() -> Result<Json<Vec<Pubsub>>, StatusCode> {
-    let pubsub_product_variant_version = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "catalog/product-variant-version/created".to_string(),
-        route: "/on-product-variant-version-creation-event".to_string(),
-    };
-    let pubsub_product_variant_updated = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "catalog/product-variant/updated".to_string(),
-        route: "/on-product-variant-updated-event".to_string(),
-    };
-    let pubsub_coupon = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "discount/coupon/created".to_string(),
-        route: "/on-id-creation-event".to_string(),
-    };
-    let pubsub_tax_rate_version = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "tax/tax-rate-version/created".to_string(),
-        route: "/on-tax-rate-version-creation-event".to_string(),
-    };
-    let pubsub_shipment_method = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "shipment/shipment-method/created".to_string(),
-        route: "/on-id-creation-event".to_string(),
-    };
-    let pubsub_user = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "user/user/created".to_string(),
-        route: "/on-id-creation-event".to_string(),
-    };
-    let pubsub_user_address = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "address/user-address/created".to_string(),
-        route: "/on-user-address-creation-event".to_string(),
-    };
-    let pubsub_user_address_archived = Pubsub {
-        pubsubname: "pubsub".to_string(),
-        topic: "address/user-address/archived".to_string(),
-        route: "/on-user-address-archived-event".to_string(),
-    };
-    Ok(Json(vec![
-        pubsub_product_variant_updated,
-        pubsub_product_variant_version,
-        pubsub_coupon,
-        pubsub_tax_rate_version,
-        pubsub_shipment_method,
-        pubsub_user,
-        pubsub_user_address,
-        pubsub_user_address_archived,
-    ]))
+    let pubsubs = TOPIC_SUBSCRIPTIONS
+        .iter()
+        .map(|(topic, route)| Pubsub {
+            pubsubname: "pubsub".to_string(),
+            topic: topic.to_string(),
+            route: route.to_string(),
+        })
+        .collect();
+    Ok(Json(pubsubs))
 }
 
 /// HTTP endpoint to receive UUID creation events.
 ///
 /// Includes all creation events that consist of only UUIDs:
 /// - `Coupon`
-/// - `ShipmentMethod`
 /// - `User`
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_id_creation_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<UuidEventData>>,
+    EventJson(event): EventJson<Event<UuidEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -214,40 +401,92 @@ pub async fn on_id_creation_event(
         "discount/coupon/created" => {
             create_in_mongodb(&state.coupon_collection, event.data.id).await?
         }
+        "user/user/created" => create_in_mongodb(&state.user_collection, event.data.id).await?,
+        _ => return Err(reject_unknown_topic(&event.topic)),
+    }
+    Ok(Json(TopicEventResponse::default()))
+}
+
+/// HTTP endpoint to receive shipment method creation events.
+///
+/// * `state` - Service state containing database connections.
+/// * `event` - Event handled by endpoint.
+#[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
+pub async fn on_shipment_method_creation_event(
+    State(state): State<HttpEventServiceState>,
+    EventJson(event): EventJson<Event<ShipmentMethodEventData>>,
+) -> Result<Json<TopicEventResponse>, StatusCode> {
+    info!("{:?}", event);
+
+    match event.topic.as_str() {
         "shipment/shipment-method/created" => {
-            create_in_mongodb(&state.shipment_method_collection, event.data.id).await?
+            let shipment_method = ShipmentMethod::from(event.data);
+            insert_shipment_method_in_mongodb(&state.shipment_method_collection, shipment_method)
+                .await?
         }
-        "user/user/created" => create_in_mongodb(&state.user_collection, event.data.id).await?,
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
+    }
+    Ok(Json(TopicEventResponse::default()))
+}
+
+/// HTTP endpoint to receive shipment method update events.
+///
+/// * `state` - Service state containing database connections.
+/// * `event` - Event handled by endpoint.
+#[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
+pub async fn on_shipment_method_updated_event(
+    State(state): State<HttpEventServiceState>,
+    EventJson(event): EventJson<Event<ShipmentMethodUpdatedEventData>>,
+) -> Result<Json<TopicEventResponse>, StatusCode> {
+    info!("{:?}", event);
+
+    match event.topic.as_str() {
+        "shipment/shipment-method/updated" => {
+            update_shipment_method_enabled_in_mongodb(&state.shipment_method_collection, event.data)
+                .await?
+        }
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
 
 /// HTTP endpoint to receive product variant version creation events.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_product_variant_version_creation_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<ProductVariantVersionEventData>>,
+    EventJson(event): EventJson<Event<ProductVariantVersionEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
     match event.topic.as_str() {
         "catalog/product-variant-version/created" => {
+            if product_variant_versions_retained() {
+                let product_variant_version = ProductVariantVersion::from(event.data.clone());
+                retain_product_variant_version_in_mongodb(
+                    &state.product_variant_version_collection,
+                    product_variant_version,
+                )
+                .await?;
+            }
             create_or_update_product_variant_in_mongodb(
                 &state.product_variant_collection,
                 event.data,
             )
             .await?;
         }
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
 
 /// HTTP endpoint to receive product variant update events.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_product_variant_update_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<UpdateProductVariantEventData>>,
+    EventJson(event): EventJson<Event<UpdateProductVariantEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -259,7 +498,7 @@ pub async fn on_product_variant_update_event(
             )
             .await?
         }
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
@@ -269,9 +508,10 @@ pub async fn on_product_variant_update_event(
 /// * `state` - Service state containing database connections.
 /// * `event` - Event handled by endpoint.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_tax_rate_version_creation_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<TaxRateVersionEventData>>,
+    EventJson(event): EventJson<Event<TaxRateVersionEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -280,7 +520,56 @@ pub async fn on_tax_rate_version_creation_event(
         "tax/tax-rate-version/created" => {
             create_or_update_tax_rate_in_mongodb(&state.tax_rate_collection, tax_rate).await?
         }
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
+    }
+    Ok(Json(TopicEventResponse::default()))
+}
+
+/// HTTP endpoint to receive tax rate deletion events.
+///
+/// Tax rates are marked as deprecated instead of deleted, so that historical orders still resolve.
+///
+/// * `state` - Service state containing database connections.
+/// * `event` - Event handled by endpoint.
+#[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
+pub async fn on_tax_rate_deleted_event(
+    State(state): State<HttpEventServiceState>,
+    EventJson(event): EventJson<Event<UuidEventData>>,
+) -> Result<Json<TopicEventResponse>, StatusCode> {
+    info!("{:?}", event);
+
+    match event.topic.as_str() {
+        "tax/tax-rate/deleted" => {
+            deprecate_tax_rate_in_mongodb(&state.tax_rate_collection, event.data.id).await?
+        }
+        _ => return Err(reject_unknown_topic(&event.topic)),
+    }
+    Ok(Json(TopicEventResponse::default()))
+}
+
+/// HTTP endpoint to receive payment information creation events.
+///
+/// * `state` - Service state containing database connections.
+/// * `event` - Event handled by endpoint.
+#[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
+pub async fn on_payment_information_creation_event(
+    State(state): State<HttpEventServiceState>,
+    EventJson(event): EventJson<Event<PaymentInformationEventData>>,
+) -> Result<Json<TopicEventResponse>, StatusCode> {
+    info!("{:?}", event);
+
+    match event.topic.as_str() {
+        "payment/payment-information/created" => {
+            let payment_information = PaymentInformation::from(event.data);
+            insert_payment_information_in_mongodb(
+                &state.payment_information_collection,
+                payment_information,
+            )
+            .await?
+        }
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
@@ -290,9 +579,10 @@ pub async fn on_tax_rate_version_creation_event(
 /// * `state` - Service state containing database connections.
 /// * `event` - Event handled by endpoint.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_user_address_creation_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<UserAddressEventData>>,
+    EventJson(event): EventJson<Event<UserAddressEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -300,7 +590,7 @@ pub async fn on_user_address_creation_event(
         "address/user-address/created" => {
             insert_user_address_in_mongodb(&state.user_collection, event.data).await?
         }
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
@@ -310,9 +600,10 @@ pub async fn on_user_address_creation_event(
 /// * `state` - Service state containing database connections.
 /// * `event` - Event handled by endpoint.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic))]
 pub async fn on_user_address_archived_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<UserAddressEventData>>,
+    EventJson(event): EventJson<Event<UserAddressEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -320,19 +611,23 @@ pub async fn on_user_address_archived_event(
         "address/user-address/archived" => {
             remove_user_address_in_mongodb(&state.user_collection, event.data).await?
         }
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
 
 /// HTTP endpoint to receive shipment creation events.
 ///
+/// Compensates the uncompensated order items of the failed shipment, redelivered events are therefore idempotent.
+/// Events of orders which can never be compensated are dropped, transient failures are redelivered.
+///
 /// * `state` - Service state containing database connections.
 /// * `event` - Event handled by endpoint.
 #[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic, order_id = %event.data.order_id))]
 pub async fn on_shipment_creation_failed_event(
     State(state): State<HttpEventServiceState>,
-    Json(event): Json<Event<ShipmentFailedEventData>>,
+    EventJson(event): EventJson<Event<ShipmentFailedEventData>>,
 ) -> Result<Json<TopicEventResponse>, StatusCode> {
     info!("{:?}", event);
 
@@ -340,11 +635,41 @@ pub async fn on_shipment_creation_failed_event(
         "shipment/shipment/creation-failed" => compensate_order(
             &state.order_collection,
             &state.order_compensation_collection,
-            event.data,
+            CompensationSource::ShipmentFailed(event.data),
+            &state.event_config,
         )
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?,
-        _ => return Err(StatusCode::INTERNAL_SERVER_ERROR),
+        .map_err(map_compensation_error)?,
+        _ => return Err(reject_unknown_topic(&event.topic)),
+    }
+    Ok(Json(TopicEventResponse::default()))
+}
+
+/// HTTP endpoint to receive payment failed events.
+///
+/// Compensates all uncompensated order items of the order with `OrderStatus::Placed` and rejects the order.
+/// Events of orders which can never be compensated are dropped, transient failures are redelivered.
+///
+/// * `state` - Service state containing database connections.
+/// * `event` - Event handled by endpoint.
+#[debug_handler(state = HttpEventServiceState)]
+#[instrument(skip_all, fields(topic = %event.topic, order_id = %event.data.order_id))]
+pub async fn on_payment_failed_event(
+    State(state): State<HttpEventServiceState>,
+    EventJson(event): EventJson<Event<PaymentFailedEventData>>,
+) -> Result<Json<TopicEventResponse>, StatusCode> {
+    info!("{:?}", event);
+
+    match event.topic.as_str() {
+        "payment/payment/failed" => compensate_order(
+            &state.order_collection,
+            &state.order_compensation_collection,
+            CompensationSource::PaymentFailed(event.data),
+            &state.event_config,
+        )
+        .await
+        .map_err(map_compensation_error)?,
+        _ => return Err(reject_unknown_topic(&event.topic)),
     }
     Ok(Json(TopicEventResponse::default()))
 }
@@ -372,12 +697,46 @@ pub async fn create_or_update_product_variant_in_mongodb(
             .await
         }
         Err(e) => {
-            log::info!("Error {:?}", e);
+            info!("Error {:?}", e);
             create_product_variant_in_mongodb(product_variant_version_event_data, collection).await
         }
     }
 }
 
+/// Defines if all product variant versions are retained, so that historical order items can resolve their exact version.
+///
+/// Read from `$PRODUCT_VARIANT_VERSIONS_RETAINED`, defaults to `true`.
+fn product_variant_versions_retained() -> bool {
+    env::var("PRODUCT_VARIANT_VERSIONS_RETAINED")
+        .ok()
+        .and_then(|retained| retained.parse::<bool>().ok())
+        .unwrap_or(true)
+}
+
+/// Retains a product variant version in MongoDB, keyed by the UUID of the product variant version.
+///
+/// Upserts the product variant version, so that redelivered events do not fail.
+///
+/// * `collection` - MongoDB collection to retain product variant version in.
+/// * `product_variant_version` - Product variant version to retain.
+async fn retain_product_variant_version_in_mongodb(
+    collection: &Collection<ProductVariantVersion>,
+    product_variant_version: ProductVariantVersion,
+) -> Result<(), StatusCode> {
+    let update_options = UpdateOptions::builder().upsert(true).build();
+    match collection
+        .update_one(
+            doc! {"_id": product_variant_version._id },
+            doc! {"$set": {"price": product_variant_version.price, "tax_rate_id": product_variant_version.tax_rate_id}},
+            update_options,
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 /// Update product variant in MongoDB.
 ///
 /// * `product_variant_version_event_data` - Product variant version event data containg new product variant version.
@@ -389,7 +748,7 @@ async fn update_product_variant_in_mongodb(
     product_variant: ProductVariant,
 ) -> Result<(), StatusCode> {
     let product_variant_version = ProductVariantVersion::from(product_variant_version_event_data);
-    log::info!("{:?}", product_variant_version);
+    info!("{:?}", product_variant_version);
     match collection
         .update_one(
             doc! {"_id": product_variant._id},
@@ -405,6 +764,9 @@ async fn update_product_variant_in_mongodb(
 
 /// Create product variant in MongoDB.
 ///
+/// Upserts the product variant, which merges the current version into a record upserted by a preceding visibility update event.
+/// The visibility of such a record is kept, otherwise the product variant is publicly visible.
+///
 /// * `product_variant_version_event_data` - Product variant version event data to create product variant with.
 /// * `collection` - MongoDB collection to create product variant in.
 async fn create_product_variant_in_mongodb(
@@ -412,12 +774,30 @@ async fn create_product_variant_in_mongodb(
     collection: &Collection<ProductVariant>,
 ) -> Result<(), StatusCode> {
     let product_variant = ProductVariant::from(product_variant_version_event_data);
-    match collection.insert_one(product_variant, None).await {
+    let update_options = UpdateOptions::builder().upsert(true).build();
+    match collection
+        .update_one(
+            doc! {"_id": product_variant._id },
+            build_product_variant_upsert(&product_variant),
+            update_options,
+        )
+        .await
+    {
         Ok(_) => Ok(()),
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
 
+/// Builds the MongoDB update of a product variant upsert, which keeps the visibility of an existing record.
+///
+/// * `product_variant` - Product variant to upsert.
+fn build_product_variant_upsert(product_variant: &ProductVariant) -> Document {
+    doc! {
+        "$set": {"current_version": product_variant.current_version},
+        "$setOnInsert": {"is_publicly_visible": product_variant.is_publicly_visible},
+    }
+}
+
 /// Create or update tax rate in MongoDB.
 ///
 /// * `collection` - MongoDB collection to create or update tax rate in.
@@ -440,6 +820,23 @@ pub async fn create_or_update_tax_rate_in_mongodb(
     }
 }
 
+/// Marks tax rate as deprecated in MongoDB.
+///
+/// * `collection` - MongoDB collection to deprecate tax rate in.
+/// * `id` - UUID of tax rate to deprecate.
+async fn deprecate_tax_rate_in_mongodb(
+    collection: &Collection<TaxRate>,
+    id: Uuid,
+) -> Result<(), StatusCode> {
+    match collection
+        .update_one(doc! {"_id": id }, doc! {"$set": {"active": false}}, None)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
 /// Inserts user address in MongoDB.
 ///
 /// * `collection` - MongoDB collection to insert user address in.
@@ -484,16 +881,69 @@ pub async fn remove_user_address_in_mongodb(
 
 /// Updates visibility of product variant in MongoDB.
 ///
+/// Events can arrive out of order, the update event can therefore precede the product variant version creation event.
+/// In that case a product variant record only capturing the visibility is upserted, which the later creation event merges into.
+///
 /// * `collection` - MongoDB collection to update the product visibility in.
 /// * `update_product_variant_event_data` - Update product variant event data containing new product visibility.
 async fn update_product_variant_visibility_in_mongodb(
     collection: &Collection<ProductVariant>,
     update_product_variant_event_data: UpdateProductVariantEventData,
 ) -> Result<(), StatusCode> {
+    let update_options = UpdateOptions::builder().upsert(true).build();
     match collection
         .update_one(
             doc! {"_id": update_product_variant_event_data.id },
             doc! {"$set": {"is_publicly_visible": update_product_variant_event_data.is_publicly_visible }},
+            update_options,
+        )
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Inserts shipment method in MongoDB.
+///
+/// * `collection` - MongoDB collection to insert shipment method in.
+/// * `shipment_method` - Shipment method to insert.
+async fn insert_shipment_method_in_mongodb(
+    collection: &Collection<ShipmentMethod>,
+    shipment_method: ShipmentMethod,
+) -> Result<(), StatusCode> {
+    match collection.insert_one(shipment_method, None).await {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Inserts payment information in MongoDB.
+///
+/// * `collection` - MongoDB collection to insert payment information in.
+/// * `payment_information` - Payment information to insert.
+async fn insert_payment_information_in_mongodb(
+    collection: &Collection<PaymentInformation>,
+    payment_information: PaymentInformation,
+) -> Result<(), StatusCode> {
+    match collection.insert_one(payment_information, None).await {
+        Ok(_) => Ok(()),
+        Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
+    }
+}
+
+/// Updates if shipment method is enabled in MongoDB.
+///
+/// * `collection` - MongoDB collection to update the shipment method in.
+/// * `shipment_method_updated_event_data` - Shipment method updated event data containing if the shipment method is enabled.
+async fn update_shipment_method_enabled_in_mongodb(
+    collection: &Collection<ShipmentMethod>,
+    shipment_method_updated_event_data: ShipmentMethodUpdatedEventData,
+) -> Result<(), StatusCode> {
+    match collection
+        .update_one(
+            doc! {"_id": shipment_method_updated_event_data.id },
+            doc! {"$set": {"enabled": shipment_method_updated_event_data.enabled }},
             None,
         )
         .await
@@ -517,3 +967,75 @@ pub async fn create_in_mongodb<T: Serialize + From<Uuid>>(
         Err(_) => Err(StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn each_topic_subscription_has_a_registered_event_handler() {
+        let registered_routes: Vec<&str> = event_handler_routes()
+            .iter()
+            .map(|(route, _)| *route)
+            .collect();
+        verify_topic_subscription_routes(&registered_routes);
+        let topics: HashSet<&str> = TOPIC_SUBSCRIPTIONS
+            .iter()
+            .map(|(topic, _)| *topic)
+            .collect();
+        assert_eq!(topics.len(), TOPIC_SUBSCRIPTIONS.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "/on-payment-failed-event")]
+    fn topic_subscription_without_event_handler_panics() {
+        let registered_routes: Vec<&str> = event_handler_routes()
+            .iter()
+            .map(|(route, _)| *route)
+            .filter(|route| *route != "/on-payment-failed-event")
+            .collect();
+        verify_topic_subscription_routes(&registered_routes);
+    }
+
+    #[tokio::test]
+    async fn topic_subscriptions_are_listed_to_dapr() {
+        let Json(pubsubs) = list_topic_subscriptions().await.unwrap();
+        let listed_subscriptions: Vec<(&str, &str)> = pubsubs
+            .iter()
+            .map(|pubsub| (pubsub.topic.as_str(), pubsub.route.as_str()))
+            .collect();
+        assert_eq!(listed_subscriptions, TOPIC_SUBSCRIPTIONS.to_vec());
+        assert!(pubsubs.iter().all(|pubsub| pubsub.pubsubname == "pubsub"));
+    }
+
+    #[test]
+    fn product_variant_upsert_keeps_visibility_of_existing_record() {
+        let product_variant_version = ProductVariantVersion {
+            _id: Uuid::new(),
+            price: 1000,
+            tax_rate_id: Uuid::new(),
+        };
+        let product_variant = ProductVariant {
+            _id: Uuid::new(),
+            current_version: product_variant_version,
+            is_publicly_visible: true,
+        };
+        let update = build_product_variant_upsert(&product_variant);
+        let set = update.get_document("$set").unwrap();
+        assert_eq!(
+            set.keys().collect::<Vec<&String>>(),
+            vec!["current_version"]
+        );
+        assert_eq!(
+            bson::from_bson::<ProductVariantVersion>(set.get("current_version").unwrap().clone())
+                .unwrap(),
+            product_variant_version
+        );
+        assert_eq!(
+            update.get_document("$setOnInsert").unwrap(),
+            &doc! {"is_publicly_visible": true}
+        );
+    }
+}
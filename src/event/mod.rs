@@ -1,3 +1,5 @@
+pub mod event_config;
 pub mod http_event_service;
 pub mod model;
 pub mod order_compensation;
+pub mod webhook;
@@ -3,6 +3,7 @@ use bson::Uuid;
 use serde::Serialize;
 
 use crate::graphql::model::{
+    gift_recipient_address::GiftRecipientAddress,
     order::{Order, OrderStatus, RejectionReason},
     payment_authorization::PaymentAuthorization,
 };
@@ -21,8 +22,8 @@ pub struct OrderDTO {
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// The status of the order.
     pub order_status: OrderStatus,
-    /// Timestamp of order placement. `None` until order is placed.
-    pub placed_at: chrono::DateTime<chrono::Utc>,
+    /// Timestamp of order placement. `None` until order is placed, always set in events.
+    pub placed_at: Option<chrono::DateTime<chrono::Utc>>,
     /// The rejection reason if status of the order is `OrderStatus::Rejected`.
     pub rejection_reason: Option<RejectionReason>,
     /// OrderItems associated with the order.
@@ -31,45 +32,76 @@ pub struct OrderDTO {
     pub shipment_address_id: Uuid,
     /// UUID of address of invoice.
     pub invoice_address_id: Uuid,
-    /// Total compensatable amount of order.
+    /// Whether the order is a gift, where the shipment address may not belong to the user.
+    pub is_gift: bool,
+    /// Free-form address of the gift recipient, which the gift order is shipped to instead of the shipment address.
+    pub gift_recipient_address: Option<GiftRecipientAddress>,
+    /// Total compensatable amount of the order items, excluding shipment fees.
     pub compensatable_order_amount: u64,
+    /// Total shipment fees of all order items.
+    pub shipping_total: u64,
+    /// Grand total of the order, the amount charged when the order is placed.
+    ///
+    /// Sum of `compensatable_order_amount` and `shipping_total`.
+    pub grand_total: u64,
     /// UUID of payment information that the order should be processed with.
     pub payment_information_id: Uuid,
     /// Optional payment authorization information.
+    ///
+    /// Only forwarded transiently in the `order/order/created` event, it is never stored.
     pub payment_authorization: Option<PaymentAuthorization>,
     /// Optional VAT number.
     pub vat_number: Option<String>,
+    /// Optional note of the customer, e.g. delivery instructions.
+    pub customer_note: Option<String>,
 }
 
-impl TryFrom<(Order, Option<PaymentAuthorization>)> for OrderDTO {
-    type Error = Error;
-
-    fn try_from(
-        (order, payment_authorization): (Order, Option<PaymentAuthorization>),
-    ) -> Result<Self, Self::Error> {
+impl From<Order> for OrderDTO {
+    /// Converts an order of any status, e.g. for exports, without payment authorization.
+    fn from(order: Order) -> Self {
         let order_item_dtos = order
             .internal_order_items
             .iter()
             .map(|order_item| OrderItemDTO::from(order_item.clone()))
             .collect();
-        let message =
-            format!("OrderDTO cannot be created, `placed_at` of the given Order is `None`");
-        let placed_at = order.placed_at.ok_or(Error::new(message))?.to_chrono();
-        let order_dto = Self {
+        Self {
             id: order._id,
             user_id: order.user._id,
             created_at: order.created_at.to_chrono(),
             order_status: order.order_status,
-            placed_at,
+            placed_at: order.placed_at.map(|placed_at| placed_at.to_chrono()),
             rejection_reason: order.rejection_reason,
             order_items: order_item_dtos,
             shipment_address_id: order.shipment_address._id,
             invoice_address_id: order.invoice_address._id,
+            is_gift: order.is_gift,
+            gift_recipient_address: order.gift_recipient_address.clone(),
+            grand_total: order.calculate_grand_total(),
             compensatable_order_amount: order.compensatable_order_amount,
+            shipping_total: order.shipping_total,
             payment_information_id: order.payment_information_id,
-            payment_authorization: payment_authorization,
+            payment_authorization: None,
             vat_number: order.vat_number,
-        };
-        Ok(order_dto)
+            customer_note: order.customer_note,
+        }
+    }
+}
+
+impl TryFrom<(Order, Option<PaymentAuthorization>)> for OrderDTO {
+    type Error = Error;
+
+    /// Converts a placed order for the order created event, fails if `placed_at` is `None`.
+    fn try_from(
+        (order, payment_authorization): (Order, Option<PaymentAuthorization>),
+    ) -> Result<Self, Self::Error> {
+        if order.placed_at.is_none() {
+            let message =
+                "OrderDTO cannot be created, `placed_at` of the given Order is `None`".to_string();
+            return Err(Error::new(message));
+        }
+        Ok(Self {
+            payment_authorization,
+            ..Self::from(order)
+        })
     }
 }
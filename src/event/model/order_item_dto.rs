@@ -24,12 +24,18 @@ pub struct OrderItemDTO {
     pub shopping_cart_item_id: Uuid,
     /// Specifies the quantity of the order item.
     pub count: u64,
-    /// Total cost of product item, which can also be refunded.
+    /// Total cost of all product items of the order item, which can also be refunded.
+    ///
+    /// Contains the cost of a single product item if `compensatable_amount_includes_count` is `false`.
     pub compensatable_amount: u64,
+    /// Whether `compensatable_amount` includes the quantity, `false` for order items stored before it did.
+    pub compensatable_amount_includes_count: bool,
     /// UUID of shipment method of order item.
     pub shipment_method_id: Uuid,
     /// UUIDs of discounts applied to order item.
     pub discount_ids: Vec<Uuid>,
+    /// UUIDs of coupons consumed by order item.
+    pub applied_coupon_ids: Vec<Uuid>,
 }
 
 impl From<OrderItem> for OrderItemDTO {
@@ -48,8 +54,10 @@ impl From<OrderItem> for OrderItemDTO {
             shopping_cart_item_id: value.shopping_cart_item._id,
             count: value.count,
             compensatable_amount: value.compensatable_amount,
+            compensatable_amount_includes_count: value.compensatable_amount_includes_count,
             shipment_method_id: value.shipment_method._id,
             discount_ids,
+            applied_coupon_ids: value.applied_coupon_ids,
         }
     }
 }
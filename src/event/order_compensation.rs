@@ -1,109 +1,612 @@
-use async_graphql::{Error, Result};
-use bson::{doc, DateTime, Uuid};
+use std::{
+    env,
+    time::{Duration, SystemTime},
+};
+
+use async_graphql::{ComplexObject, Context, Error, ErrorExtensions, Result, SimpleObject, Value};
+use bson::{doc, DateTime, Document, Uuid};
 use futures::TryStreamExt;
 use mongodb::Collection;
 use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
 
-use crate::graphql::{model::order::Order, mutation::validate_object, query::query_object};
+use crate::{
+    graphql::{
+        model::{
+            money::Money,
+            order::{Order, OrderStatus, RejectionReason},
+        },
+        mutation::set_status_rejected_in_mongodb,
+        query::query_object,
+    },
+    store_config::StoreConfig,
+};
 
 use super::{
-    http_event_service::ShipmentFailedEventData,
+    event_config::EventConfig,
+    http_event_service::{PaymentFailedEventData, ShipmentFailedEventData},
     model::order_compensation_dto::OrderCompensationDTO,
+    webhook::dispatch_to_webhooks,
 };
 
 /// Models an order compensation that is sent as an event and logged in MongoDB.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, SimpleObject)]
+#[graphql(complex)]
 pub struct OrderCompensation {
     /// Order compensation UUID.
     pub _id: Uuid,
     /// UUID of the order.
     pub order_id: Uuid,
-    /// UUIDs of the order items of shipment.
+    /// UUIDs of the compensated order items.
     pub order_item_ids: Vec<Uuid>,
     /// Timestamp when compensation was triggered.
     pub triggered_at: DateTime,
     /// Amount of order compensation.
+    #[graphql(skip)]
     pub amount_to_compensate: u64,
+    /// Optional reason of order compensation, set for manually triggered refunds.
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Whether the order compensation created event was successfully published.
+    ///
+    /// Set only after successful publication, unpublished order compensations are republished by `republish_unpublished_order_compensations`.
+    /// Order compensations stored before this attribute existed are assumed to be published, so that no refund is sent twice.
+    #[graphql(skip)]
+    #[serde(default = "default_event_published")]
+    pub event_published: bool,
 }
 
-/// Responsible for compensating a shipment based on a failed shipment event. Saves compensation in MongoDB.
+/// Default of `OrderCompensation::event_published` for order compensations stored before the attribute existed.
+fn default_event_published() -> bool {
+    true
+}
+
+/// Default interval between republications of unpublished order compensation created events in seconds.
+const DEFAULT_ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS: u64 = 60;
+
+#[ComplexObject]
+impl OrderCompensation {
+    /// Amount of order compensation.
+    async fn amount_to_compensate<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.amount_to_compensate, &store_config.currency)
+    }
+}
+
+/// Source event type which triggers an order compensation.
+#[derive(Debug)]
+pub enum CompensationSource {
+    /// Shipment of order items failed, compensates the order items of the shipment.
+    ShipmentFailed(ShipmentFailedEventData),
+    /// Payment of an order failed, compensates all order items and rejects the order with `RejectionReason::PaymentFailed`.
+    PaymentFailed(PaymentFailedEventData),
+}
+
+/// Error code of compensations, which can never be applied, e.g. as the order is not `OrderStatus::Placed`.
+///
+/// Events causing such compensations are dropped instead of being redelivered.
+const COMPENSATION_NOT_APPLICABLE_ERROR_CODE: &str = "COMPENSATION_NOT_APPLICABLE";
+
+/// Responsible for compensating an order based on a failed shipment or payment event. Saves compensation in MongoDB.
+///
+/// Only compensates order items, which are not yet compensated, so that redelivered events are idempotent.
+/// Fails with an error checked by `is_compensation_not_applicable` if the order can never be compensated.
 ///
 /// * `order_collection` - MongoDB collection to validate order with.
 /// * `order_compensation_collection` - MongoDB collection to compensate order in.
-/// * `shipment_failed_event_data` - Event data of failed shipment event containing UUID of order to compensate.
+/// * `compensation_source` - Source event type containing UUID of order to compensate.
+/// * `event_config` - Configuration of the published compensation event.
 pub async fn compensate_order(
     order_collection: &Collection<Order>,
     order_compensation_collection: &Collection<OrderCompensation>,
-    shipment_failed_event_data: ShipmentFailedEventData,
+    compensation_source: CompensationSource,
+    event_config: &EventConfig,
 ) -> Result<()> {
-    validate_object(&order_collection, shipment_failed_event_data.order_id).await?;
-    verify_items_uncompensated(
-        &order_compensation_collection,
-        &shipment_failed_event_data.order_item_ids,
+    match compensation_source {
+        CompensationSource::ShipmentFailed(shipment_failed_event_data) => {
+            let order_id = shipment_failed_event_data.order_id;
+            let order = query_compensatable_order(order_collection, order_id).await?;
+            let order_item_ids = shipment_failed_event_data.order_item_ids;
+            verify_items_contained_in_order(&order, &order_item_ids)
+                .map_err(|error| build_compensation_not_applicable_error(error.message))?;
+            let uncompensated_order_item_ids =
+                query_uncompensated_order_item_ids(order_compensation_collection, order_item_ids)
+                    .await?;
+            if uncompensated_order_item_ids.is_empty() {
+                info!(
+                    "Order items of shipment of order of UUID: `{}` are already compensated.",
+                    order_id
+                );
+                return Ok(());
+            }
+            verify_order_placed(&order)?;
+            compensate_order_items(
+                order_collection,
+                order_compensation_collection,
+                order_id,
+                uncompensated_order_item_ids,
+                None,
+                event_config,
+            )
+            .await?;
+        }
+        CompensationSource::PaymentFailed(payment_failed_event_data) => {
+            let order_id = payment_failed_event_data.order_id;
+            let order = query_compensatable_order(order_collection, order_id).await?;
+            if order.order_status == OrderStatus::Rejected
+                && order.rejection_reason == Some(RejectionReason::PaymentFailed)
+            {
+                info!(
+                    "Order of UUID: `{}` is already rejected as its payment failed.",
+                    order_id
+                );
+                return Ok(());
+            }
+            verify_order_placed(&order)?;
+            let order_item_ids = order
+                .internal_order_items
+                .iter()
+                .map(|order_item| order_item._id)
+                .collect();
+            let uncompensated_order_item_ids =
+                query_uncompensated_order_item_ids(order_compensation_collection, order_item_ids)
+                    .await?;
+            if !uncompensated_order_item_ids.is_empty() {
+                compensate_order_items(
+                    order_collection,
+                    order_compensation_collection,
+                    order_id,
+                    uncompensated_order_item_ids,
+                    None,
+                    event_config,
+                )
+                .await?;
+            }
+            set_status_rejected_in_mongodb(
+                order_collection,
+                order_id,
+                OrderStatus::Placed,
+                RejectionReason::PaymentFailed,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Checks if an error of `compensate_order` describes a compensation, which can never be applied.
+///
+/// * `error` - Error returned by `compensate_order`.
+pub fn is_compensation_not_applicable(error: &Error) -> bool {
+    error
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("code"))
+        .is_some_and(|code| *code == Value::from(COMPENSATION_NOT_APPLICABLE_ERROR_CODE))
+}
+
+/// Builds the error of a compensation, which can never be applied.
+///
+/// * `message` - Message describing why the compensation can not be applied.
+fn build_compensation_not_applicable_error(message: String) -> Error {
+    Error::new(message)
+        .extend_with(|_, extensions| extensions.set("code", COMPENSATION_NOT_APPLICABLE_ERROR_CODE))
+}
+
+/// Queries the order to compensate, an order which does not exist can never be compensated.
+///
+/// * `order_collection` - MongoDB collection containing the order.
+/// * `order_id` - UUID of order to compensate.
+async fn query_compensatable_order(
+    order_collection: &Collection<Order>,
+    order_id: Uuid,
+) -> Result<Order> {
+    match order_collection
+        .find_one(doc! {"_id": order_id }, None)
+        .await
+    {
+        Ok(Some(order)) => Ok(order),
+        Ok(None) => {
+            let message = format!("Order of UUID: `{}` to compensate not found.", order_id);
+            Err(build_compensation_not_applicable_error(message))
+        }
+        Err(_) => {
+            let message = format!("Order of UUID: `{}` could not be retrieved.", order_id);
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Verifies that an order is `OrderStatus::Placed`, only placed orders can be compensated or refunded.
+///
+/// * `order` - Order to compensate.
+fn verify_order_placed(order: &Order) -> Result<()> {
+    match order.order_status {
+        OrderStatus::Placed => Ok(()),
+        _ => {
+            let message = format!(
+                "`{:?}` must be `OrderStatus::Placed` to be able to compensate order of UUID: `{}`.",
+                order.order_status, order._id
+            );
+            Err(build_compensation_not_applicable_error(message))
+        }
+    }
+}
+
+/// Responsible for refunding order items of an order manually. Saves compensation in MongoDB.
+///
+/// Only order items of an order with `OrderStatus::Placed` can be refunded.
+/// Returns the created order compensation.
+///
+/// * `order_collection` - MongoDB collection containing order to refund order items of.
+/// * `order_compensation_collection` - MongoDB collection to compensate order in.
+/// * `order_id` - UUID of order to refund order items of.
+/// * `order_item_ids` - UUIDs of order items to refund.
+/// * `reason` - Reason of refund.
+/// * `event_config` - Configuration of the published compensation event.
+pub async fn refund_order_items(
+    order_collection: &Collection<Order>,
+    order_compensation_collection: &Collection<OrderCompensation>,
+    order_id: Uuid,
+    order_item_ids: Vec<Uuid>,
+    reason: String,
+    event_config: &EventConfig,
+) -> Result<OrderCompensation> {
+    let order = query_object(order_collection, order_id).await?;
+    verify_order_placed(&order)?;
+    verify_items_contained_in_order(&order, &order_item_ids)?;
+    compensate_order_items(
+        order_collection,
+        order_compensation_collection,
+        order_id,
+        order_item_ids,
+        Some(reason),
+        event_config,
     )
-    .await?;
+    .await
+}
+
+/// Compensates order items of an order, saves the compensation in MongoDB and sends the compensation event.
+///
+/// * `order_collection` - MongoDB collection containing order to calculate compensatable amount from.
+/// * `order_compensation_collection` - MongoDB collection to compensate order in.
+/// * `order_id` - UUID of order to compensate.
+/// * `order_item_ids` - UUIDs of order items to compensate.
+/// * `reason` - Optional reason of compensation.
+/// * `event_config` - Configuration of the published compensation event.
+async fn compensate_order_items(
+    order_collection: &Collection<Order>,
+    order_compensation_collection: &Collection<OrderCompensation>,
+    order_id: Uuid,
+    order_item_ids: Vec<Uuid>,
+    reason: Option<String>,
+    event_config: &EventConfig,
+) -> Result<OrderCompensation> {
+    verify_items_uncompensated(order_compensation_collection, &order_item_ids).await?;
     let amount_to_compensate =
-        calculate_amount_to_compensate(&order_collection, &shipment_failed_event_data).await?;
+        calculate_amount_to_compensate(order_collection, order_id, &order_item_ids).await?;
+    verify_amount_within_compensatable_order_amount(
+        order_collection,
+        order_compensation_collection,
+        order_id,
+        amount_to_compensate,
+    )
+    .await?;
     let order_compensation = OrderCompensation {
         _id: Uuid::new(),
-        order_id: shipment_failed_event_data.order_id,
-        order_item_ids: shipment_failed_event_data.order_item_ids,
+        order_id,
+        order_item_ids,
         triggered_at: DateTime::now(),
         amount_to_compensate,
+        reason,
+        event_published: false,
     };
     insert_order_compensation_in_mongodb(&order_compensation_collection, &order_compensation)
         .await?;
-    send_order_compensation_event(order_compensation).await
+    publish_order_compensation(
+        order_compensation_collection,
+        event_config,
+        order_compensation,
+    )
+    .await
+}
+
+/// Sends the order compensation created event and marks the order compensation as `event_published` afterwards.
+///
+/// If the service fails between the insertion and the publication, the event is republished by `republish_unpublished_order_compensations`.
+///
+/// * `collection` - MongoDB collection containing the order compensation.
+/// * `event_config` - Configuration of the published compensation event.
+/// * `order_compensation` - Order compensation to publish.
+async fn publish_order_compensation(
+    collection: &Collection<OrderCompensation>,
+    event_config: &EventConfig,
+    order_compensation: OrderCompensation,
+) -> Result<OrderCompensation> {
+    send_order_compensation_event(event_config, order_compensation.clone()).await?;
+    set_event_published_in_mongodb(collection, order_compensation._id).await?;
+    Ok(OrderCompensation {
+        event_published: true,
+        ..order_compensation
+    })
+}
+
+/// Periodically republishes the events of order compensations, which are not marked as `event_published`.
+///
+/// Ensures at-least-once delivery of order compensation created events, consumers must therefore deduplicate by order compensation UUID.
+/// Only order compensations triggered at least one interval ago are republished, so that publications in progress are not duplicated.
+/// The interval is read from `$ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS` and defaults to `DEFAULT_ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS`, `0` disables the republication.
+///
+/// * `collection` - MongoDB collection of order compensations.
+/// * `event_config` - Configuration of the published compensation event.
+pub async fn republish_unpublished_order_compensations(
+    collection: Collection<OrderCompensation>,
+    event_config: EventConfig,
+) {
+    let republish_interval_seconds = env::var("ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS);
+    if republish_interval_seconds == 0 {
+        info!("Republication of unpublished order compensations is disabled.");
+        return;
+    }
+    let republish_interval = Duration::from_secs(republish_interval_seconds);
+    let mut interval = tokio::time::interval(republish_interval);
+    loop {
+        interval.tick().await;
+        let triggered_before = DateTime::from(SystemTime::now() - republish_interval);
+        if let Err(error) =
+            republish_order_compensations(&collection, &event_config, triggered_before).await
+        {
+            warn!(
+                "Republishing unpublished order compensations failed: {}",
+                error.message
+            );
+        }
+    }
+}
+
+/// Republishes the events of all unpublished order compensations triggered before a timestamp.
+///
+/// Failed republications are logged and retried in the next interval.
+///
+/// * `collection` - MongoDB collection of order compensations.
+/// * `event_config` - Configuration of the published compensation event.
+/// * `triggered_before` - Timestamp which the order compensations must be triggered before.
+async fn republish_order_compensations(
+    collection: &Collection<OrderCompensation>,
+    event_config: &EventConfig,
+    triggered_before: DateTime,
+) -> Result<()> {
+    let filter = build_unpublished_order_compensations_filter(triggered_before);
+    let unpublished_order_compensations: Vec<OrderCompensation> =
+        match collection.find(filter, None).await {
+            Ok(cursor) => cursor.try_collect().await?,
+            Err(_) => {
+                return Err(Error::new(
+                    "Unpublished order compensations could not be retrieved.",
+                ))
+            }
+        };
+    for order_compensation in unpublished_order_compensations {
+        let id = order_compensation._id;
+        match publish_order_compensation(collection, event_config, order_compensation).await {
+            Ok(_) => info!("Republished order compensation of UUID: `{}`.", id),
+            Err(error) => warn!(
+                "Republishing order compensation of UUID: `{}` failed: {}",
+                id, error.message
+            ),
+        }
+    }
+    Ok(())
 }
 
-/// Calculates the amount that the compensation event should compensate. Based on the failed shipment event.
+/// Builds the MongoDB filter of unpublished order compensations triggered before a timestamp.
+///
+/// * `triggered_before` - Timestamp which the order compensations must be triggered before.
+fn build_unpublished_order_compensations_filter(triggered_before: DateTime) -> Document {
+    doc! {"event_published": false, "triggered_at": {"$lt": triggered_before}}
+}
+
+/// Verifies that all order items are part of the order, otherwise returns an error.
+///
+/// * `order` - Order which should contain the order items.
+/// * `order_item_ids` - UUIDs of order items to verify.
+fn verify_items_contained_in_order(order: &Order, order_item_ids: &[Uuid]) -> Result<()> {
+    let missing_order_item_ids: Vec<&Uuid> = order_item_ids
+        .iter()
+        .filter(|id| {
+            !order
+                .internal_order_items
+                .iter()
+                .any(|order_item| order_item._id == **id)
+        })
+        .collect();
+    match missing_order_item_ids.len() {
+        0 => Ok(()),
+        _ => {
+            let message = format!(
+                "Order items of UUIDs: `{:?}` are not contained in order of UUID: `{}`.",
+                missing_order_item_ids, order._id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Calculates the amount that the compensation should compensate. Based on the order items to compensate.
 ///
 /// * `order_collection` - MongoDB collection containing order to calculate compensatable amount from.
-/// * `shipment_failed_event_data` - Event data of failed shipment event containing UUID of order to calculate compensatable amount for.
+/// * `order_id` - UUID of order to calculate compensatable amount for.
+/// * `order_item_ids` - UUIDs of order items to calculate compensatable amount for.
 async fn calculate_amount_to_compensate(
     order_collection: &Collection<Order>,
-    shipment_failed_event_data: &ShipmentFailedEventData,
+    order_id: Uuid,
+    order_item_ids: &[Uuid],
 ) -> Result<u64> {
-    let order = query_object(&order_collection, shipment_failed_event_data.order_id).await?;
+    let order = query_object(order_collection, order_id).await?;
     let compensatable_amounts: Vec<u64> = order
         .internal_order_items
         .iter()
-        .filter(|order_item| {
-            shipment_failed_event_data
-                .order_item_ids
-                .contains(&order_item._id)
-        })
+        .filter(|order_item| order_item_ids.contains(&order_item._id))
         .map(|order_item| order_item.compensatable_amount)
         .collect();
     let amount_to_compensate = compensatable_amounts.iter().sum();
     Ok(amount_to_compensate)
 }
 
+/// Verifies that the amount to compensate together with all prior compensations of the order does not exceed the compensatable order amount.
+///
+/// Protects against compensating an order multiple times, otherwise returns an error.
+///
+/// * `order_collection` - MongoDB collection containing order to verify compensatable order amount of.
+/// * `order_compensation_collection` - MongoDB collection of order compensations.
+/// * `order_id` - UUID of order to verify.
+/// * `amount_to_compensate` - Amount of the new order compensation.
+async fn verify_amount_within_compensatable_order_amount(
+    order_collection: &Collection<Order>,
+    order_compensation_collection: &Collection<OrderCompensation>,
+    order_id: Uuid,
+    amount_to_compensate: u64,
+) -> Result<()> {
+    let order = query_object(order_collection, order_id).await?;
+    let message = format!(
+        "Order compensations of order with UUID: `{}` could not be retrieved.",
+        order_id
+    );
+    let prior_order_compensations: Vec<OrderCompensation> = match order_compensation_collection
+        .find(doc! {"order_id": order_id }, None)
+        .await
+    {
+        Ok(cursor) => cursor.try_collect().await?,
+        Err(_) => return Err(Error::new(message)),
+    };
+    check_amount_within_compensatable_order_amount(
+        &order,
+        &prior_order_compensations,
+        amount_to_compensate,
+    )
+}
+
+/// Checks that the amount to compensate together with prior order compensations does not exceed the compensatable order amount.
+///
+/// * `order` - Order to check compensatable order amount of.
+/// * `prior_order_compensations` - Prior order compensations of the order.
+/// * `amount_to_compensate` - Amount of the new order compensation.
+fn check_amount_within_compensatable_order_amount(
+    order: &Order,
+    prior_order_compensations: &[OrderCompensation],
+    amount_to_compensate: u64,
+) -> Result<()> {
+    let compensated_amount: u64 = prior_order_compensations
+        .iter()
+        .map(|order_compensation| order_compensation.amount_to_compensate)
+        .sum();
+    match compensated_amount + amount_to_compensate <= order.compensatable_order_amount {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "Compensating `{}` for order of UUID: `{}` exceeds its compensatable order amount: `{}`, `{}` was already compensated.",
+                amount_to_compensate, order._id, order.compensatable_order_amount, compensated_amount
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
 /// Verifies that all of the items are uncompensated, otherwise returns an error.
 ///
+/// Queries all order compensations which contain at least one of the order items.
+/// A partial overlap with a prior order compensation is therefore detected as well.
+///
 /// * `order_compensation_collection` - MongoDB collection of order compensations.
 /// * `order_item_ids` - UUIDs of order items to verify as uncompensated.
 async fn verify_items_uncompensated(
     order_compensation_collection: &Collection<OrderCompensation>,
-    order_item_ids: &Vec<Uuid>,
+    order_item_ids: &[Uuid],
 ) -> Result<()> {
-    let query = doc! {"order_item_ids": {"$not": {"$elemMatch": {"$in": order_item_ids}}}};
+    let compensated_order_item_ids =
+        query_compensated_order_item_ids(order_compensation_collection, order_item_ids).await?;
+    match compensated_order_item_ids.len() {
+        0 => Ok(()),
+        _ => {
+            let message = format!(
+                "Order items of UUIDs: `{:?}` are already compensated.",
+                compensated_order_item_ids
+            );
+            Err(build_compensation_not_applicable_error(message))
+        }
+    }
+}
+
+/// Filters the order items, which are not contained in any prior order compensation.
+///
+/// * `order_compensation_collection` - MongoDB collection of order compensations.
+/// * `order_item_ids` - UUIDs of order items to filter.
+async fn query_uncompensated_order_item_ids(
+    order_compensation_collection: &Collection<OrderCompensation>,
+    order_item_ids: Vec<Uuid>,
+) -> Result<Vec<Uuid>> {
+    let compensated_order_item_ids =
+        query_compensated_order_item_ids(order_compensation_collection, &order_item_ids).await?;
+    Ok(order_item_ids
+        .into_iter()
+        .filter(|id| !compensated_order_item_ids.contains(id))
+        .collect())
+}
+
+/// Queries the order items, which are contained in prior order compensations.
+///
+/// * `order_compensation_collection` - MongoDB collection of order compensations.
+/// * `order_item_ids` - UUIDs of order items to find in prior order compensations.
+async fn query_compensated_order_item_ids(
+    order_compensation_collection: &Collection<OrderCompensation>,
+    order_item_ids: &[Uuid],
+) -> Result<Vec<Uuid>> {
+    let query = build_compensated_order_item_ids_filter(order_item_ids);
     let message = format!(
         "Order items of UUIDs: `{:?}` could not be verfied.",
         order_item_ids
     );
     match order_compensation_collection.find(query, None).await {
         Ok(cursor) => {
-            let objects: Vec<OrderCompensation> = cursor.try_collect().await?;
-            match objects.len() {
-                0 => Ok(()),
-                _ => Err(Error::new(message)),
-            }
+            let order_compensations: Vec<OrderCompensation> = cursor.try_collect().await?;
+            Ok(find_compensated_order_item_ids(
+                &order_compensations,
+                order_item_ids,
+            ))
         }
         Err(_) => Err(Error::new(message)),
     }
 }
 
+/// Builds the MongoDB filter of order compensations, which contain at least one of the order items.
+///
+/// * `order_item_ids` - UUIDs of order items to find in prior order compensations.
+fn build_compensated_order_item_ids_filter(order_item_ids: &[Uuid]) -> Document {
+    doc! {"order_item_ids": {"$in": order_item_ids}}
+}
+
+/// Finds the order items that are contained in prior order compensations.
+///
+/// * `order_compensations` - Prior order compensations.
+/// * `order_item_ids` - UUIDs of order items to find in prior order compensations.
+fn find_compensated_order_item_ids(
+    order_compensations: &[OrderCompensation],
+    order_item_ids: &[Uuid],
+) -> Vec<Uuid> {
+    order_item_ids
+        .iter()
+        .filter(|id| {
+            order_compensations
+                .iter()
+                .any(|order_compensation| order_compensation.order_item_ids.contains(id))
+        })
+        .cloned()
+        .collect()
+}
+
 /// Inserts order compenstation in MongoDB.
 ///
 /// * `collection` - MongoDB collection to insert order compensation in.
@@ -118,16 +621,165 @@ async fn insert_order_compensation_in_mongodb(
     }
 }
 
-/// Sends an `order/order/compensate` created event containing the amount to compensate.
+/// Marks an order compensation as `event_published` in MongoDB.
+///
+/// * `collection` - MongoDB collection containing the order compensation.
+/// * `id` - UUID of order compensation to mark as published.
+async fn set_event_published_in_mongodb(
+    collection: &Collection<OrderCompensation>,
+    id: Uuid,
+) -> Result<()> {
+    let result = collection
+        .update_one(
+            doc! {"_id": id },
+            doc! {"$set": {"event_published": true}},
+            None,
+        )
+        .await;
+    match result {
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let message = format!(
+                "Marking order compensation of id: `{}` as published failed in MongoDB.",
+                id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Sends an order compensation created event containing the amount to compensate.
+///
+/// The topic defaults to `order/order-compensation/created`.
 ///
+/// * `event_config` - Configuration of the published compensation event.
 /// * `order_compensation` - Order compensation to create event with.
-async fn send_order_compensation_event(order_compensation: OrderCompensation) -> Result<()> {
+async fn send_order_compensation_event(
+    event_config: &EventConfig,
+    order_compensation: OrderCompensation,
+) -> Result<()> {
     let client = reqwest::Client::new();
     let order_compensation_dto = OrderCompensationDTO::from(order_compensation);
+    let topic = &event_config.order_compensation_created_topic;
     client
-        .post("http://localhost:3500/v1.0/publish/pubsub/order/order-compensation/created")
+        .post(event_config.publish_url(topic))
         .json(&order_compensation_dto)
         .send()
-        .await?;
+        .await?
+        .error_for_status()?;
+    dispatch_to_webhooks(topic, &order_compensation_dto);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{build_order, build_order_item};
+
+    /// Builds an order compensation of order items of an order.
+    fn build_order_compensation(
+        order_id: Uuid,
+        order_item_ids: Vec<Uuid>,
+        amount_to_compensate: u64,
+    ) -> OrderCompensation {
+        OrderCompensation {
+            _id: Uuid::new(),
+            order_id,
+            order_item_ids,
+            triggered_at: DateTime::now(),
+            amount_to_compensate,
+            reason: None,
+            event_published: true,
+        }
+    }
+
+    #[test]
+    fn compensations_up_to_the_compensatable_order_amount_are_accepted() {
+        let order = build_order(
+            vec![
+                build_order_item(Uuid::new(), 1000, 1),
+                build_order_item(Uuid::new(), 500, 1),
+            ],
+            0,
+        );
+        let prior_order_compensations = vec![build_order_compensation(
+            order._id,
+            vec![order.internal_order_items[0]._id],
+            1000,
+        )];
+        assert!(check_amount_within_compensatable_order_amount(
+            &order,
+            &prior_order_compensations,
+            500
+        )
+        .is_ok());
+        assert!(check_amount_within_compensatable_order_amount(
+            &order,
+            &prior_order_compensations,
+            501
+        )
+        .is_err());
+        assert!(check_amount_within_compensatable_order_amount(&order, &[], 1501).is_err());
+    }
+
+    #[test]
+    fn only_order_items_of_overlapping_compensations_are_compensated() {
+        let order_id = Uuid::new();
+        let order_item_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new()).collect();
+        let unrelated_order_item_id = Uuid::new();
+        let order_compensations = vec![build_order_compensation(
+            order_id,
+            vec![order_item_ids[1], unrelated_order_item_id],
+            1000,
+        )];
+        assert_eq!(
+            find_compensated_order_item_ids(&order_compensations, &order_item_ids),
+            vec![order_item_ids[1]]
+        );
+        assert!(find_compensated_order_item_ids(&[], &order_item_ids).is_empty());
+    }
+
+    #[test]
+    fn compensated_order_items_query_matches_any_overlapping_compensation() {
+        let order_item_ids = vec![Uuid::new(), Uuid::new()];
+        assert_eq!(
+            build_compensated_order_item_ids_filter(&order_item_ids),
+            doc! {"order_item_ids": {"$in": [order_item_ids[0], order_item_ids[1]]}}
+        );
+    }
+
+    #[test]
+    fn legacy_order_compensations_count_as_published() {
+        let order_compensation = build_order_compensation(Uuid::new(), vec![Uuid::new()], 1000);
+        let mut document = bson::to_document(&order_compensation).unwrap();
+        document.remove("event_published");
+        document.remove("reason");
+        let legacy_order_compensation: OrderCompensation = bson::from_document(document).unwrap();
+        assert!(legacy_order_compensation.event_published);
+        assert_eq!(legacy_order_compensation.reason, None);
+        let unpublished_order_compensation = OrderCompensation {
+            event_published: false,
+            ..order_compensation
+        };
+        let document = bson::to_document(&unpublished_order_compensation).unwrap();
+        assert!(
+            !bson::from_document::<OrderCompensation>(document)
+                .unwrap()
+                .event_published
+        );
+    }
+
+    #[test]
+    fn unpublished_order_compensations_filter_excludes_publications_in_progress() {
+        let triggered_before = DateTime::from_millis(1_000);
+        let filter = build_unpublished_order_compensations_filter(triggered_before);
+        assert_eq!(filter.get_bool("event_published"), Ok(false));
+        assert_eq!(
+            filter
+                .get_document("triggered_at")
+                .unwrap()
+                .get_datetime("$lt"),
+            Ok(&triggered_before)
+        );
+    }
+}
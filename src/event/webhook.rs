@@ -0,0 +1,85 @@
+use std::{env, sync::OnceLock, time::Duration};
+
+use serde::Serialize;
+use tracing::warn;
+
+/// Name of the HTTP header containing the topic of the event sent to a webhook.
+const TOPIC_HEADER: &str = "X-Event-Topic";
+
+/// Returns the webhook URLs that receive order events.
+///
+/// Read from the comma-separated `$ORDER_EVENT_WEBHOOK_URLS`, no webhooks are registered if it is not set.
+fn webhook_urls() -> Vec<String> {
+    env::var("ORDER_EVENT_WEBHOOK_URLS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|url| url.trim())
+        .filter(|url| !url.is_empty())
+        .map(|url| url.to_string())
+        .collect()
+}
+
+/// Default timeout of a webhook delivery in milliseconds.
+const DEFAULT_WEBHOOK_TIMEOUT_MILLISECONDS: u64 = 5000;
+
+/// HTTP client shared by all webhook deliveries.
+static WEBHOOK_CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+/// Returns the HTTP client shared by all webhook deliveries, built on first use.
+///
+/// The timeout of a delivery is read from `$WEBHOOK_TIMEOUT_MILLISECONDS` and defaults to `DEFAULT_WEBHOOK_TIMEOUT_MILLISECONDS`.
+fn webhook_client() -> &'static reqwest::Client {
+    WEBHOOK_CLIENT.get_or_init(|| {
+        let timeout_milliseconds = env::var("WEBHOOK_TIMEOUT_MILLISECONDS")
+            .ok()
+            .and_then(|timeout| timeout.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_WEBHOOK_TIMEOUT_MILLISECONDS);
+        reqwest::Client::builder()
+            .timeout(Duration::from_millis(timeout_milliseconds))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Sends an event to all registered webhooks, mirroring the event published to Dapr.
+///
+/// Deliveries are spawned as background tasks, so that slow webhooks do not delay the order operation.
+/// Failed deliveries are logged, but do not return an error, as webhooks must not fail the order operation.
+///
+/// * `topic` - Topic of the event, sent in the `X-Event-Topic` header.
+/// * `payload` - Event data which is sent as JSON body.
+pub fn dispatch_to_webhooks<T: Serialize>(topic: &str, payload: &T) {
+    let urls = webhook_urls();
+    if urls.is_empty() {
+        return;
+    }
+    let payload = match serde_json::to_value(payload) {
+        Ok(payload) => payload,
+        Err(error) => {
+            warn!(
+                "Serializing event of topic: `{}` for webhooks failed: {}",
+                topic, error
+            );
+            return;
+        }
+    };
+    for url in urls {
+        let topic = topic.to_string();
+        let payload = payload.clone();
+        tokio::spawn(async move {
+            let result = webhook_client()
+                .post(&url)
+                .header(TOPIC_HEADER, &topic)
+                .json(&payload)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(error) = result {
+                warn!(
+                    "Delivering event of topic: `{}` to webhook: `{}` failed: {}",
+                    topic, url, error
+                );
+            }
+        });
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use async_graphql::SimpleObject;
+use bson::Uuid;
+
+use super::{foreign_types::Coupon, order_item::OrderItem};
+
+/// Coupon applied to order items of an order.
+///
+/// The coupon is resolved to its details, e.g. its code and description, by the discount service via federation.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct AppliedCoupon {
+    /// Applied coupon.
+    pub coupon: Coupon,
+    /// UUIDs of the order items the coupon was applied to.
+    pub order_item_ids: Vec<Uuid>,
+}
+
+/// Collects each distinct coupon applied to order items, sorted by coupon UUID.
+///
+/// * `order_items` - Order items to collect applied coupons of.
+pub fn collect_applied_coupons(order_items: &[OrderItem]) -> Vec<AppliedCoupon> {
+    let mut order_item_ids_by_coupon_ids: BTreeMap<Uuid, Vec<Uuid>> = BTreeMap::new();
+    for order_item in order_items {
+        for coupon_id in &order_item.applied_coupon_ids {
+            order_item_ids_by_coupon_ids
+                .entry(*coupon_id)
+                .or_default()
+                .push(order_item._id);
+        }
+    }
+    order_item_ids_by_coupon_ids
+        .into_iter()
+        .map(|(coupon_id, order_item_ids)| AppliedCoupon {
+            coupon: Coupon::from(coupon_id),
+            order_item_ids,
+        })
+        .collect()
+}
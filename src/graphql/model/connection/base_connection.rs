@@ -1,6 +1,11 @@
+use std::env;
+
 use async_graphql::{OutputType, SimpleObject};
 use mongodb_cursor_pagination::FindResult;
 
+/// Maximum amount of nodes of a connection page, if `$MAX_PAGE_SIZE` is not set.
+const DEFAULT_MAX_PAGE_SIZE: usize = 100;
+
 /// A base connection for an output type.
 #[derive(SimpleObject)]
 #[graphql(shareable)]
@@ -34,3 +39,17 @@ where
         }
     }
 }
+
+/// Clamps the requested amount of nodes of a connection page to the maximum page size.
+///
+/// The maximum page size is read from `$MAX_PAGE_SIZE` and defaults to `DEFAULT_MAX_PAGE_SIZE`.
+/// Clients need to paginate using `skip` to retrieve further nodes.
+///
+/// * `first` - Requested amount of nodes, the maximum page size if `None`.
+pub fn clamp_page_size(first: Option<usize>) -> usize {
+    let max_page_size = env::var("MAX_PAGE_SIZE")
+        .ok()
+        .and_then(|max_page_size| max_page_size.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_MAX_PAGE_SIZE);
+    first.map_or(max_page_size, |first| first.min(max_page_size))
+}
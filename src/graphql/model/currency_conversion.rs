@@ -0,0 +1,51 @@
+use async_graphql::{Error, Result, SimpleObject};
+use bson::DateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::store_config::StoreConfig;
+
+/// Conversion of the amounts of an order to the base currency, recorded at order creation for accounting reconciliation.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, SimpleObject)]
+pub struct CurrencyConversion {
+    /// ISO 4217 code of the base currency.
+    pub base_currency: String,
+    /// Exchange rate from the currency of the store to the base currency, `1.0` if the order is in the base currency.
+    pub exchange_rate: f64,
+    /// Timestamp when the exchange rate was determined.
+    pub determined_at: DateTime,
+}
+
+impl CurrencyConversion {
+    /// Determines the currency conversion of an order from the store configuration.
+    ///
+    /// Orders in the base currency use an exchange rate of `1.0`.
+    /// Fails if the currency of the store differs from the base currency and no exchange rate is configured.
+    ///
+    /// * `store_config` - Store configuration containing the currencies and the configured exchange rate.
+    /// * `determined_at` - Timestamp when the exchange rate is determined, the order creation timestamp.
+    pub fn determine(store_config: &StoreConfig, determined_at: DateTime) -> Result<Self> {
+        let exchange_rate = if store_config.currency == store_config.base_currency {
+            1.0
+        } else {
+            store_config.exchange_rate.ok_or_else(|| {
+                let message = format!(
+                    "No exchange rate from `{}` to base currency `{}` is configured in `$EXCHANGE_RATE`.",
+                    store_config.currency, store_config.base_currency
+                );
+                Error::new(message)
+            })?
+        };
+        Ok(Self {
+            base_currency: store_config.base_currency.clone(),
+            exchange_rate,
+            determined_at,
+        })
+    }
+
+    /// Converts an amount in minor units of the currency of the store to minor units of the base currency, rounded half away from zero.
+    ///
+    /// * `amount` - Amount in minor units of the currency of the store.
+    pub fn convert(&self, amount: u64) -> u64 {
+        (amount as f64 * self.exchange_rate).round() as u64
+    }
+}
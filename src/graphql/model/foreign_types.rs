@@ -4,7 +4,10 @@ use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, hash::Hash};
 
 use crate::{
-    event::http_event_service::{ProductVariantVersionEventData, TaxRateVersionEventData},
+    event::http_event_service::{
+        PaymentInformationEventData, ProductVariantVersionEventData, ShipmentMethodEventData,
+        TaxRateVersionEventData,
+    },
     graphql::mutation::get_discounts::GetDiscountsFindApplicableDiscountsDiscounts,
 };
 
@@ -155,6 +158,15 @@ pub struct TaxRate {
     /// Current version of tax rate.
     #[graphql(skip)]
     pub current_version: TaxRateVersion,
+    /// Defines if tax rate is active, deprecated tax rates are kept for historical orders.
+    #[graphql(skip)]
+    #[serde(default = "default_tax_rate_active")]
+    pub active: bool,
+}
+
+/// Tax rates stored before deprecation was tracked are active.
+fn default_tax_rate_active() -> bool {
+    true
 }
 
 impl From<TaxRateVersionEventData> for TaxRate {
@@ -162,6 +174,7 @@ impl From<TaxRateVersionEventData> for TaxRate {
         Self {
             _id: value.tax_rate_id,
             current_version: TaxRateVersion::from(value),
+            active: true,
         }
     }
 }
@@ -169,7 +182,9 @@ impl From<TaxRateVersionEventData> for TaxRate {
 impl From<TaxRate> for Bson {
     fn from(value: TaxRate) -> Self {
         let current_version_bson = Bson::from(value.current_version);
-        Bson::Document(doc!("_id": value._id, "current_version": current_version_bson))
+        Bson::Document(
+            doc!("_id": value._id, "current_version": current_version_bson, "active": value.active),
+        )
     }
 }
 
@@ -193,6 +208,18 @@ pub struct TaxRateVersion {
     pub version: u32,
 }
 
+/// Basis points of a rate of `1.0`, e.g. a rate of `0.19` is `1900` basis points.
+pub const BASIS_POINTS_PER_UNIT: u64 = 10_000;
+
+impl TaxRateVersion {
+    /// Returns the rate in basis points, which allows exact tax calculations in integer arithmetic.
+    ///
+    /// The rate is synchronized as a floating-point number and rounded to the nearest basis point, negative rates are clamped to zero.
+    pub fn rate_basis_points(&self) -> u64 {
+        (self.rate * BASIS_POINTS_PER_UNIT as f64).round().max(0.0) as u64
+    }
+}
+
 impl From<TaxRateVersionEventData> for TaxRateVersion {
     fn from(value: TaxRateVersionEventData) -> Self {
         Self {
@@ -315,6 +342,19 @@ impl From<Uuid> for UserAddress {
 pub struct ShipmentMethod {
     /// UUID of the shipment method.
     pub _id: Uuid,
+    /// Estimated amount of days the shipment method requires for delivery.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub estimated_delivery_days: Option<u32>,
+    /// Defines if shipment method can be selected for new orders.
+    #[graphql(skip)]
+    #[serde(default = "default_shipment_method_enabled")]
+    pub enabled: bool,
+}
+
+/// Shipment methods stored before the enabled flag existed are enabled.
+fn default_shipment_method_enabled() -> bool {
+    true
 }
 
 impl PartialOrd for ShipmentMethod {
@@ -325,7 +365,9 @@ impl PartialOrd for ShipmentMethod {
 
 impl From<ShipmentMethod> for Bson {
     fn from(value: ShipmentMethod) -> Self {
-        Bson::Document(doc!("_id": value._id))
+        Bson::Document(
+            doc!("_id": value._id, "estimated_delivery_days": value.estimated_delivery_days, "enabled": value.enabled),
+        )
     }
 }
 
@@ -337,6 +379,46 @@ impl From<ShipmentMethod> for Uuid {
 
 impl From<Uuid> for ShipmentMethod {
     fn from(value: Uuid) -> Self {
-        ShipmentMethod { _id: value }
+        ShipmentMethod {
+            _id: value,
+            estimated_delivery_days: None,
+            enabled: true,
+        }
+    }
+}
+
+impl From<ShipmentMethodEventData> for ShipmentMethod {
+    fn from(value: ShipmentMethodEventData) -> Self {
+        ShipmentMethod {
+            _id: value.id,
+            estimated_delivery_days: value.estimated_delivery_days,
+            enabled: true,
+        }
+    }
+}
+
+/// Foreign type of a payment information.
+#[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq, Copy, Clone, SimpleObject)]
+#[graphql(unresolvable)]
+pub struct PaymentInformation {
+    /// UUID of the payment information.
+    pub _id: Uuid,
+    /// UUID of the user owning the payment information.
+    #[graphql(skip)]
+    pub user_id: Uuid,
+}
+
+impl From<PaymentInformation> for Uuid {
+    fn from(value: PaymentInformation) -> Self {
+        value._id
+    }
+}
+
+impl From<PaymentInformationEventData> for PaymentInformation {
+    fn from(value: PaymentInformationEventData) -> Self {
+        PaymentInformation {
+            _id: value.id,
+            user_id: value.user_id,
+        }
     }
 }
@@ -0,0 +1,100 @@
+use async_graphql::{Error, Result, SimpleObject};
+use serde::{Deserialize, Serialize};
+
+use super::super::mutation_input_structs::GiftRecipientAddressInput;
+
+/// Maximum length of each field of a gift recipient address in characters.
+const MAX_GIFT_RECIPIENT_ADDRESS_FIELD_LENGTH: usize = 200;
+
+/// Free-form address of the recipient of a gift order, who is not required to be a user.
+///
+/// Gift orders with a gift recipient address are shipped to it instead of a registered user address.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, SimpleObject)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftRecipientAddress {
+    /// Name of the gift recipient.
+    pub name: String,
+    /// Street and house number.
+    pub street: String,
+    /// Postal code.
+    pub postal_code: String,
+    /// City.
+    pub city: String,
+    /// ISO 3166-1 alpha-2 code of the country.
+    pub country: String,
+}
+
+impl TryFrom<GiftRecipientAddressInput> for GiftRecipientAddress {
+    type Error = Error;
+
+    /// Trims each field and uppercases the country code.
+    ///
+    /// Fails if a field is empty or exceeds `MAX_GIFT_RECIPIENT_ADDRESS_FIELD_LENGTH` characters, or if the country is not a two letter code.
+    fn try_from(value: GiftRecipientAddressInput) -> Result<Self, Self::Error> {
+        let gift_recipient_address = Self {
+            name: validate_field("name", &value.name)?,
+            street: validate_field("street", &value.street)?,
+            postal_code: validate_field("postalCode", &value.postal_code)?,
+            city: validate_field("city", &value.city)?,
+            country: validate_field("country", &value.country)?.to_uppercase(),
+        };
+        let country = &gift_recipient_address.country;
+        if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+            let message = format!(
+                "Country: `{}` of gift recipient address is not an ISO 3166-1 alpha-2 code.",
+                country
+            );
+            return Err(Error::new(message));
+        }
+        Ok(gift_recipient_address)
+    }
+}
+
+/// Trims a field of a gift recipient address and checks that it is neither empty nor too long.
+///
+/// * `field_name` - Name of the field, used in the error message.
+/// * `value` - Value of the field.
+fn validate_field(field_name: &str, value: &str) -> Result<String> {
+    let trimmed_value = value.trim();
+    let length = trimmed_value.chars().count();
+    if length == 0 || length > MAX_GIFT_RECIPIENT_ADDRESS_FIELD_LENGTH {
+        let message = format!(
+            "Field `{}` of gift recipient address must contain 1 to `{}` characters.",
+            field_name, MAX_GIFT_RECIPIENT_ADDRESS_FIELD_LENGTH
+        );
+        return Err(Error::new(message));
+    }
+    Ok(trimmed_value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a gift recipient address input with a country.
+    fn build_input(country: &str) -> GiftRecipientAddressInput {
+        GiftRecipientAddressInput {
+            name: " Erika Mustermann ".to_string(),
+            street: "Heidestraße 17".to_string(),
+            postal_code: "51147".to_string(),
+            city: "Köln".to_string(),
+            country: country.to_string(),
+        }
+    }
+
+    #[test]
+    fn gift_recipient_address_is_normalized() {
+        let gift_recipient_address = GiftRecipientAddress::try_from(build_input("de")).unwrap();
+        assert_eq!(gift_recipient_address.name, "Erika Mustermann");
+        assert_eq!(gift_recipient_address.country, "DE");
+    }
+
+    #[test]
+    fn invalid_gift_recipient_addresses_are_rejected() {
+        assert!(GiftRecipientAddress::try_from(build_input("DEU")).is_err());
+        assert!(GiftRecipientAddress::try_from(build_input("Ö ")).is_err());
+        let mut input = build_input("DE");
+        input.street = "  ".to_string();
+        assert!(GiftRecipientAddress::try_from(input).is_err());
+    }
+}
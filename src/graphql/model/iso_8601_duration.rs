@@ -0,0 +1,230 @@
+use std::time::Duration;
+
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+const SECONDS_PER_WEEK: u64 = 7 * SECONDS_PER_DAY;
+
+/// Duration of a time window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Iso8601Duration(pub Duration);
+
+/// Duration of a time window, serialized as an ISO 8601 duration, e.g. `P1DT2H30M` or `PT90S`.
+///
+/// Years and months are not supported, as their length varies.
+#[Scalar]
+impl ScalarType for Iso8601Duration {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        if let Value::String(string) = &value {
+            return parse_iso_8601_duration(string)
+                .map(Iso8601Duration)
+                .map_err(InputValueError::custom);
+        }
+        Err(InputValueError::expected_type(value))
+    }
+
+    fn to_value(&self) -> Value {
+        Value::String(format_iso_8601_duration(self.0))
+    }
+}
+
+impl From<Iso8601Duration> for Duration {
+    fn from(value: Iso8601Duration) -> Self {
+        value.0
+    }
+}
+
+/// Parses an ISO 8601 duration of the form `PnWnDTnHnMnS`, returns a descriptive error message for malformed durations.
+///
+/// Each designator is optional but must occur at most once, only seconds may have a fraction.
+///
+/// * `value` - ISO 8601 duration to parse, e.g. `P1DT2H30M`.
+pub fn parse_iso_8601_duration(value: &str) -> Result<Duration, String> {
+    let malformed_message =
+        |reason: &str| format!("`{}` is not a valid ISO 8601 duration: {}.", value, reason);
+    let designators = value
+        .strip_prefix('P')
+        .ok_or_else(|| malformed_message("must start with `P`"))?;
+    let (date_designators, time_designators) = match designators.split_once('T') {
+        Some((_, "")) => return Err(malformed_message("`T` must be followed by a time")),
+        Some((date_designators, time_designators)) => (date_designators, Some(time_designators)),
+        None => (designators, None),
+    };
+    if date_designators.is_empty() && time_designators.is_none() {
+        return Err(malformed_message("must contain at least one designator"));
+    }
+    let mut seconds = 0.0;
+    for (amount, unit) in split_designators(date_designators).map_err(|e| malformed_message(&e))? {
+        seconds += amount
+            * match unit {
+                'W' => SECONDS_PER_WEEK,
+                'D' => SECONDS_PER_DAY,
+                'Y' | 'M' => {
+                    return Err(malformed_message(
+                        "years and months are not supported, as their length varies",
+                    ))
+                }
+                _ => {
+                    return Err(malformed_message(&format!(
+                        "unknown date designator `{}`",
+                        unit
+                    )))
+                }
+            } as f64;
+    }
+    for (amount, unit) in
+        split_designators(time_designators.unwrap_or("")).map_err(|e| malformed_message(&e))?
+    {
+        seconds += amount
+            * match unit {
+                'H' => SECONDS_PER_HOUR,
+                'M' => SECONDS_PER_MINUTE,
+                'S' => 1,
+                _ => {
+                    return Err(malformed_message(&format!(
+                        "unknown time designator `{}`",
+                        unit
+                    )))
+                }
+            } as f64;
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|_| malformed_message("duration is out of range"))
+}
+
+/// Splits designators like `1D12H` into amounts and units, checking that units are unique and only the last amount has a fraction.
+///
+/// * `designators` - Date or time designators of an ISO 8601 duration, without `P` and `T`.
+fn split_designators(designators: &str) -> Result<Vec<(f64, char)>, String> {
+    let mut amounts_and_units: Vec<(f64, char)> = vec![];
+    let mut amount = String::new();
+    for character in designators.chars() {
+        if character.is_ascii_digit() || character == '.' {
+            amount.push(character);
+            continue;
+        }
+        if amount.is_empty() {
+            return Err(format!("designator `{}` is missing an amount", character));
+        }
+        if amounts_and_units.iter().any(|(_, unit)| *unit == character) {
+            return Err(format!("designator `{}` occurs multiple times", character));
+        }
+        if amount.contains('.') && character != 'S' {
+            return Err("only seconds may have a fraction".to_string());
+        }
+        let parsed_amount = amount
+            .parse::<f64>()
+            .map_err(|_| format!("amount `{}` is not a number", amount))?;
+        amounts_and_units.push((parsed_amount, character));
+        amount.clear();
+    }
+    match amount.is_empty() {
+        true => Ok(amounts_and_units),
+        false => Err(format!("amount `{}` is missing a designator", amount)),
+    }
+}
+
+/// Formats a duration as an ISO 8601 duration, e.g. `P1DT2H30M`, `PT0S` for zero durations.
+///
+/// * `duration` - Duration to format, sub-second precision is dropped.
+pub fn format_iso_8601_duration(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    let days = total_seconds / SECONDS_PER_DAY;
+    let hours = total_seconds % SECONDS_PER_DAY / SECONDS_PER_HOUR;
+    let minutes = total_seconds % SECONDS_PER_HOUR / SECONDS_PER_MINUTE;
+    let seconds = total_seconds % SECONDS_PER_MINUTE;
+    let mut formatted = String::from("P");
+    if days > 0 {
+        formatted.push_str(&format!("{}D", days));
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || days == 0 {
+        formatted.push('T');
+        if hours > 0 {
+            formatted.push_str(&format!("{}H", hours));
+        }
+        if minutes > 0 {
+            formatted.push_str(&format!("{}M", minutes));
+        }
+        if seconds > 0 || total_seconds == 0 {
+            formatted.push_str(&format!("{}S", seconds));
+        }
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_8601_durations_are_parsed() {
+        assert_eq!(
+            parse_iso_8601_duration("P1DT2H30M"),
+            Ok(Duration::from_secs(
+                SECONDS_PER_DAY + 2 * SECONDS_PER_HOUR + 30 * SECONDS_PER_MINUTE
+            ))
+        );
+        assert_eq!(
+            parse_iso_8601_duration("P2W"),
+            Ok(Duration::from_secs(2 * SECONDS_PER_WEEK))
+        );
+        assert_eq!(
+            parse_iso_8601_duration("PT1M"),
+            Ok(Duration::from_secs(SECONDS_PER_MINUTE))
+        );
+        assert_eq!(
+            parse_iso_8601_duration("PT1.5S"),
+            Ok(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_iso_8601_duration("PT0S"), Ok(Duration::ZERO));
+    }
+
+    #[test]
+    fn malformed_iso_8601_durations_are_rejected() {
+        for value in [
+            "", "1D", "P", "PT", "P1M", "P1Y", "P1.5D", "PT1H1H", "PTH", "PT1", "P1X", "PT1..5S",
+        ] {
+            assert!(parse_iso_8601_duration(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn formatted_iso_8601_durations_are_parsed_to_the_same_duration() {
+        for seconds in [
+            0,
+            59,
+            SECONDS_PER_HOUR,
+            SECONDS_PER_DAY + 61,
+            3 * SECONDS_PER_WEEK,
+        ] {
+            let duration = Duration::from_secs(seconds);
+            let formatted = format_iso_8601_duration(duration);
+            assert_eq!(
+                parse_iso_8601_duration(&formatted),
+                Ok(duration),
+                "{}",
+                formatted
+            );
+        }
+        assert_eq!(
+            format_iso_8601_duration(Duration::from_secs(SECONDS_PER_DAY + 90)),
+            "P1DT1M30S"
+        );
+        assert_eq!(
+            format_iso_8601_duration(Duration::from_secs(SECONDS_PER_DAY)),
+            "P1D"
+        );
+    }
+
+    #[test]
+    fn iso_8601_duration_scalar_rejects_non_strings() {
+        assert!(Iso8601Duration::parse(Value::from(60)).is_err());
+        assert_eq!(
+            Iso8601Duration::parse(Value::from("PT1H"))
+                .unwrap()
+                .to_value(),
+            Value::from("PT1H")
+        );
+    }
+}
@@ -1,7 +1,18 @@
+pub mod applied_coupon;
 pub mod connection;
+pub mod currency_conversion;
 pub mod foreign_types;
+pub mod gift_recipient_address;
+pub mod iso_8601_duration;
+pub mod money;
 pub mod order;
+pub mod order_creation_result;
 pub mod order_datatypes;
+pub mod order_integrity;
 pub mod order_item;
+pub mod order_preview;
+pub mod order_summary;
+pub mod order_timeline;
 pub mod payment_authorization;
+pub mod retained_product_variant_version;
 pub mod user;
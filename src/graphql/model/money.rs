@@ -0,0 +1,188 @@
+use async_graphql::{
+    indexmap::IndexMap, InputValueError, InputValueResult, Name, Number, Result, Scalar,
+    ScalarType, Value,
+};
+
+/// Locale used to format monetary amounts if the requested locale is unknown.
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Number of decimal places of the minor units of all supported currencies.
+const MINOR_UNIT_DECIMAL_PLACES: u32 = 2;
+
+/// Formatting conventions of a locale for monetary amounts.
+struct LocaleFormat {
+    /// Separator between groups of three integer digits.
+    grouping_separator: &'static str,
+    /// Separator between integer and fractional digits.
+    decimal_separator: &'static str,
+    /// Whether the currency symbol precedes the amount.
+    symbol_before_amount: bool,
+}
+
+impl LocaleFormat {
+    /// Returns the formatting conventions of a locale, `None` if the locale is unknown.
+    ///
+    /// * `locale` - BCP 47 language tag, e.g. `de-DE`.
+    fn from_locale(locale: &str) -> Option<Self> {
+        let (grouping_separator, decimal_separator, symbol_before_amount) =
+            match locale.replace('_', "-").to_lowercase().as_str() {
+                "en-us" | "en-gb" | "en" => (",", ".", true),
+                "de-de" | "de-at" | "de" | "es-es" | "es" | "it-it" | "it" | "nl-nl" | "nl" => {
+                    (".", ",", false)
+                }
+                "de-ch" => ("'", ".", true),
+                "fr-fr" | "fr" => ("\u{202f}", ",", false),
+                _ => return None,
+            };
+        Some(Self {
+            grouping_separator,
+            decimal_separator,
+            symbol_before_amount,
+        })
+    }
+}
+
+/// Monetary amount in minor units of a currency, e.g. cents for `EUR`.
+///
+/// Serialized as `{ amount: Int, currency: String }`, where `currency` is an ISO 4217 code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Money {
+    /// Amount in minor units of the currency.
+    pub amount: i64,
+    /// ISO 4217 code of the currency.
+    pub currency: String,
+}
+
+impl Money {
+    /// Constructs money from an amount in minor units.
+    ///
+    /// * `amount` - Amount in minor units, as it is stored internally.
+    /// * `currency` - ISO 4217 code of the currency, the currency of the store for all internally stored amounts.
+    pub fn from_minor_units(amount: u64, currency: &str) -> Result<Self> {
+        Ok(Self {
+            amount: i64::try_from(amount)?,
+            currency: currency.to_string(),
+        })
+    }
+
+    /// Formats money as a localized string, e.g. `€1,234.56` for `en-US` or `1.234,56 €` for `de-DE`.
+    ///
+    /// Falls back to `DEFAULT_LOCALE` if the locale is unknown.
+    ///
+    /// * `locale` - BCP 47 language tag, e.g. `de-DE`.
+    pub fn format(&self, locale: &str) -> String {
+        let locale_format = LocaleFormat::from_locale(locale)
+            .or_else(|| LocaleFormat::from_locale(DEFAULT_LOCALE))
+            .unwrap();
+        let minor_units_per_unit = 10_u64.pow(MINOR_UNIT_DECIMAL_PLACES);
+        let absolute_amount = self.amount.unsigned_abs();
+        let integer_digits = (absolute_amount / minor_units_per_unit).to_string();
+        let fractional_digits = absolute_amount % minor_units_per_unit;
+        let grouped_integer_digits = integer_digits
+            .as_bytes()
+            .rchunks(3)
+            .rev()
+            .map(|digits| std::str::from_utf8(digits).unwrap())
+            .collect::<Vec<&str>>()
+            .join(locale_format.grouping_separator);
+        let number = format!(
+            "{}{}{}{:0width$}",
+            if self.amount < 0 { "-" } else { "" },
+            grouped_integer_digits,
+            locale_format.decimal_separator,
+            fractional_digits,
+            width = MINOR_UNIT_DECIMAL_PLACES as usize
+        );
+        let symbol = currency_symbol(&self.currency);
+        match locale_format.symbol_before_amount {
+            true => format!("{}{}", symbol, number),
+            false => format!("{} {}", number, symbol),
+        }
+    }
+}
+
+/// Returns the symbol of a currency, or its ISO 4217 code if no symbol is known.
+///
+/// * `currency` - ISO 4217 code of the currency.
+fn currency_symbol(currency: &str) -> &str {
+    match currency {
+        "EUR" => "€",
+        "USD" => "$",
+        "GBP" => "£",
+        "CHF" => "CHF ",
+        _ => currency,
+    }
+}
+
+/// Monetary amount in minor units of a currency, serialized as `{ amount: Int, currency: String }`.
+#[Scalar]
+impl ScalarType for Money {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        if let Value::Object(object) = &value {
+            let maybe_amount = match object.get("amount") {
+                Some(Value::Number(amount)) => amount.as_i64(),
+                _ => None,
+            };
+            let maybe_currency = match object.get("currency") {
+                Some(Value::String(currency)) => Some(currency.clone()),
+                _ => None,
+            };
+            if let (Some(amount), Some(currency)) = (maybe_amount, maybe_currency) {
+                if amount < 0 {
+                    return Err(InputValueError::custom(
+                        "`amount` of `Money` must not be negative.",
+                    ));
+                }
+                if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()) {
+                    return Err(InputValueError::custom(
+                        "`currency` of `Money` must be an ISO 4217 code.",
+                    ));
+                }
+                return Ok(Self { amount, currency });
+            }
+        }
+        Err(InputValueError::expected_type(value))
+    }
+
+    fn to_value(&self) -> Value {
+        let mut object = IndexMap::new();
+        object.insert(
+            Name::new("amount"),
+            Value::Number(Number::from(self.amount)),
+        );
+        object.insert(Name::new("currency"), Value::String(self.currency.clone()));
+        Value::Object(object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::value;
+
+    use super::*;
+
+    #[test]
+    fn money_is_serialized_with_amount_and_currency() {
+        let money = Money::from_minor_units(1299, "EUR").unwrap();
+        assert_eq!(
+            money.to_value(),
+            value!({ "amount": 1299, "currency": "EUR" })
+        );
+        assert_eq!(Money::parse(money.to_value()).unwrap(), money);
+    }
+
+    #[test]
+    fn invalid_money_is_rejected() {
+        assert!(Money::parse(value!({ "amount": -1, "currency": "EUR" })).is_err());
+        assert!(Money::parse(value!({ "amount": 1, "currency": "eur" })).is_err());
+        assert!(Money::from_minor_units(u64::MAX, "EUR").is_err());
+    }
+
+    #[test]
+    fn money_is_formatted_by_locale() {
+        let money = Money::from_minor_units(123456, "EUR").unwrap();
+        assert_eq!(money.format("en-US"), "€1,234.56");
+        assert_eq!(money.format("de-DE"), "1.234,56 €");
+        assert_eq!(money.format("xx-XX"), "€1,234.56");
+    }
+}
@@ -1,14 +1,34 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::BTreeSet, time::SystemTime};
 
-use async_graphql::{ComplexObject, Enum, Result, SimpleObject};
-use bson::Uuid;
+use async_graphql::{ComplexObject, Context, Enum, Result, SimpleObject};
 use bson::{datetime::DateTime, Bson};
+use bson::{doc, Uuid};
+use mongodb::{Collection, Database};
 use serde::{Deserialize, Serialize};
 
-use super::connection::order_item_connection::OrderItemConnection;
-use super::foreign_types::UserAddress;
-use super::order_datatypes::{CommonOrderInput, OrderDirection};
-use super::order_item::OrderItem;
+use crate::{
+    authorization::authorize_user,
+    collection_names::{ORDER_COMPENSATIONS, SHIPMENT_METHODS},
+    event::order_compensation::OrderCompensation,
+    graphql::{
+        mutation::PENDING_TIMEOUT,
+        query::{query_objects, query_order_compensations_of_order},
+    },
+    store_config::StoreConfig,
+};
+
+use super::applied_coupon::{collect_applied_coupons, AppliedCoupon};
+use super::connection::{
+    base_connection::clamp_page_size, discount_connection::DiscountConnection,
+    order_item_connection::OrderItemConnection,
+};
+use super::currency_conversion::CurrencyConversion;
+use super::foreign_types::{Discount, ShipmentMethod, UserAddress};
+use super::gift_recipient_address::GiftRecipientAddress;
+use super::money::Money;
+use super::order_datatypes::{CommonOrderField, CommonOrderInput, OrderDirection};
+use super::order_item::{sort_discounts, OrderItem};
+use super::order_timeline::{build_order_timeline, OrderTimelineEntry};
 use super::user::User;
 
 /// The order of a user.
@@ -25,31 +45,314 @@ pub struct Order {
     pub order_status: OrderStatus,
     /// Timestamp of order placement. `None` until order is placed.
     pub placed_at: Option<DateTime>,
+    /// Timestamp of the last order mutation.
+    ///
+    /// `None` for orders stored before this attribute existed, resolved via `created_at` in that case.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub last_updated_at: Option<DateTime>,
     /// The rejection reason if status of the order is `OrderStatus::Rejected`.
     pub rejection_reason: Option<RejectionReason>,
     /// The internal vector consisting of order items.
     #[graphql(skip)]
     pub internal_order_items: Vec<OrderItem>,
     /// Address to where the order should be shipped to.
-    #[graphql(skip)]
     pub shipment_address: UserAddress,
     /// Address of invoice.
     pub invoice_address: UserAddress,
-    /// Total compensatable amount of order.
+    /// Whether the order is a gift, where the shipment address may not belong to the user.
+    #[serde(default)]
+    pub is_gift: bool,
+    /// Free-form address of the gift recipient, which the gift order is shipped to instead of the shipment address.
+    ///
+    /// `None` for orders which are no gifts or whose gift recipient is a registered address.
+    #[serde(default)]
+    pub gift_recipient_address: Option<GiftRecipientAddress>,
+    /// Total compensatable amount of the order items, excluding shipment fees.
+    ///
+    /// Only order items are compensatable, the amount charged when the order is placed is `calculate_grand_total`.
+    #[graphql(skip)]
     pub compensatable_order_amount: u64,
+    /// Total shipment fees of all order items, as calculated by the shipment service at order creation.
+    ///
+    /// `0` for orders stored before this attribute existed.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub shipping_total: u64,
     /// UUID of payment information that the order should be processed with.
     pub payment_information_id: Uuid,
     /// Optional VAT number.
     #[graphql(skip)]
     pub vat_number: Option<String>,
+    /// Optional note of the customer, e.g. delivery instructions.
+    #[serde(default)]
+    pub customer_note: Option<String>,
+    /// Timeline of the status transitions of the order, starting with the status at creation.
+    ///
+    /// Empty for orders stored before this attribute existed.
+    #[serde(default)]
+    pub status_history: Vec<StatusHistoryEntry>,
+    /// Whether the `order/order/created` event of the order was successfully published.
+    ///
+    /// Set only after successful publication, placed orders with unpublished events are republished by `republish_unpublished_order_created_events`.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub event_published: bool,
+    /// Whether a payment authorization was provided on order placement.
+    ///
+    /// The payment authorization itself, e.g. the CVC, is never stored and only forwarded in the `order/order/created` event.
+    #[serde(default)]
+    pub payment_authorization_provided: bool,
+    /// Conversion of the amounts of the order to the base currency, recorded at order creation.
+    ///
+    /// `None` for orders stored before this attribute existed.
+    #[serde(default)]
+    pub currency_conversion: Option<CurrencyConversion>,
+}
+
+impl Order {
+    /// Calculates the total tax contained in the order, sum of the tax amounts of the order items.
+    pub fn calculate_tax_amount(&self) -> u64 {
+        self.internal_order_items
+            .iter()
+            .map(|order_item| order_item.calculate_tax_amount())
+            .sum()
+    }
+
+    /// Calculates the subtotal of the discounted prices of all order items, excluding shipment fees and tax.
+    pub fn calculate_items_subtotal(&self) -> u64 {
+        self.internal_order_items
+            .iter()
+            .map(|order_item| order_item.compensatable_amount - order_item.calculate_tax_amount())
+            .sum()
+    }
+
+    /// Calculates the grand total of the order including shipment fees and tax, the amount charged when the order is placed.
+    pub fn calculate_grand_total(&self) -> u64 {
+        self.compensatable_order_amount + self.shipping_total
+    }
+
+    /// Calculates the amount refunded by all order compensations of the order, authorizing the user owning the order.
+    ///
+    /// * `ctx` - GraphQL context containing the `Authorized-User` header and the database client.
+    async fn calculate_refunded_amount<'a>(&self, ctx: &Context<'a>) -> Result<u64> {
+        authorize_user(ctx, Some(self.user._id))?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<OrderCompensation> =
+            db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS);
+        let order_compensations = query_order_compensations_of_order(&collection, self._id).await?;
+        Ok(sum_refunded_amount(&order_compensations))
+    }
+
+    /// Calculates the grand total of the order minus the refunded amount, which does not drop below zero.
+    ///
+    /// * `refunded_amount` - Amount refunded by all order compensations of the order.
+    pub fn calculate_net_amount(&self, refunded_amount: u64) -> u64 {
+        self.calculate_grand_total().saturating_sub(refunded_amount)
+    }
+
+    /// Checks if the order was created less than `PENDING_TIMEOUT` before a timestamp.
+    ///
+    /// * `timestamp` - Timestamp to check the pending timeout against.
+    pub fn is_within_pending_timeout(&self, timestamp: SystemTime) -> bool {
+        self.created_at.to_system_time() + PENDING_TIMEOUT >= timestamp
+    }
+
+    /// Timestamp when the pending timeout of the order expires, `None` if the order is not `OrderStatus::Pending`.
+    pub fn pending_timeout_expiry(&self) -> Option<DateTime> {
+        match self.order_status {
+            OrderStatus::Pending => Some(DateTime::from_system_time(
+                self.created_at.to_system_time() + PENDING_TIMEOUT,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Checks if the order can be placed at a timestamp, returns the reason otherwise.
+    ///
+    /// An order can be placed if it is `OrderStatus::Pending` and within the pending timeout.
+    ///
+    /// * `timestamp` - Timestamp of the potential order placement.
+    pub fn check_placeable(&self, timestamp: SystemTime) -> Result<(), String> {
+        if self.order_status != OrderStatus::Pending {
+            return Err(format!(
+                "`{:?}` must be `OrderStatus::Pending` to be able to be placed. Order was already placed or rejected.",
+                self.order_status
+            ));
+        }
+        if !self.is_within_pending_timeout(timestamp) {
+            return Err(
+                "Order is `OrderStatus::Pending` for too long and can not be placed anymore."
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
 }
 
 #[ComplexObject]
 impl Order {
+    /// Whether the order can currently be placed.
+    ///
+    /// Based on the order status and the pending timeout, does not check the availability of the order items.
+    async fn can_be_placed(&self) -> bool {
+        self.check_placeable(SystemTime::now()).is_ok()
+    }
+
+    /// Reason why the order can currently not be placed, `None` if it can be placed.
+    async fn cannot_be_placed_reason(&self) -> Option<String> {
+        self.check_placeable(SystemTime::now()).err()
+    }
+
+    /// Timestamp when the order can no longer be placed because of the pending timeout.
+    ///
+    /// `None` if the order is not `OrderStatus::Pending`.
+    async fn pending_expires_at(&self) -> Option<DateTime> {
+        self.pending_timeout_expiry()
+    }
+
+    /// Remaining seconds until the order can no longer be placed because of the pending timeout, zero if already expired.
+    ///
+    /// `None` if the order is not `OrderStatus::Pending`.
+    async fn seconds_until_expiry(&self) -> Option<u64> {
+        self.pending_timeout_expiry().map(|pending_expires_at| {
+            pending_expires_at
+                .to_system_time()
+                .duration_since(SystemTime::now())
+                .map(|remaining| remaining.as_secs())
+                .unwrap_or(0)
+        })
+    }
+
+    /// Timestamp of the last order mutation.
+    async fn last_updated_at(&self) -> DateTime {
+        self.last_updated_at.unwrap_or(self.created_at)
+    }
+
+    /// Total compensatable amount of the order items, excluding shipment fees.
+    async fn compensatable_order_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.compensatable_order_amount, &store_config.currency)
+    }
+
+    /// Total amount refunded by all order compensations of the order.
+    ///
+    /// Authorizes the user owning the order.
+    async fn refunded_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let refunded_amount = self.calculate_refunded_amount(ctx).await?;
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(refunded_amount, &store_config.currency)
+    }
+
+    /// Grand total of the order minus the refunded amount.
+    ///
+    /// Authorizes the user owning the order.
+    async fn net_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let refunded_amount = self.calculate_refunded_amount(ctx).await?;
+        let net_amount = self.calculate_net_amount(refunded_amount);
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(net_amount, &store_config.currency)
+    }
+
+    /// Total tax contained in the order, sum of the tax amounts of the order items.
+    ///
+    /// Available while the order is `OrderStatus::Pending` and matches the tax at placement, as tax rate versions are fixed at creation.
+    async fn tax_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.calculate_tax_amount(), &store_config.currency)
+    }
+
+    /// Subtotal of the discounted prices of all order items, excluding shipment fees and tax.
+    async fn items_subtotal<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.calculate_items_subtotal(), &store_config.currency)
+    }
+
+    /// Grand total of the order including shipment fees and tax, which is charged when the order is placed.
+    ///
+    /// Sent as `grand_total` in the `order/order/created` event. Equals the sum of `items_subtotal`, `shipping_total` and `tax_amount`, as prices are gross prices.
+    async fn grand_total<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.calculate_grand_total(), &store_config.currency)
+    }
+
+    /// Grand total of the order converted to the base currency with the exchange rate recorded at order creation.
+    ///
+    /// `None` for orders stored before the currency conversion was recorded.
+    async fn base_currency_amount(&self) -> Result<Option<Money>> {
+        self.currency_conversion
+            .as_ref()
+            .map(|currency_conversion| {
+                Money::from_minor_units(
+                    currency_conversion.convert(self.calculate_grand_total()),
+                    &currency_conversion.base_currency,
+                )
+            })
+            .transpose()
+    }
+
+    /// Total shipment fees of all order items, to be shown as shipping line in the order summary.
+    async fn shipping_total<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.shipping_total, &store_config.currency)
+    }
+
+    /// Grand total of the order including shipment fees and tax, formatted as a localized string.
+    ///
+    /// Formats the amount of `grand_total`. Formatted with the locale of the store if no locale is requested.
+    async fn total_formatted<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(
+            desc = "BCP 47 language tag of the locale to format the total with, e.g. `de-DE`."
+        )]
+        locale: Option<String>,
+    ) -> Result<String> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        let money = Money::from_minor_units(self.calculate_grand_total(), &store_config.currency)?;
+        Ok(money.format(&locale.unwrap_or(store_config.locale.clone())))
+    }
+
+    /// Number of order items of the order.
+    ///
+    /// Avoids resolving the `order_items` connection when only the amount of order items is required.
+    async fn item_count(&self) -> u64 {
+        self.internal_order_items.len() as u64
+    }
+
+    /// Estimated delivery date of the order.
+    ///
+    /// Calculated by adding the estimated delivery days of the slowest shipment method among the order items to `placed_at`, or to `created_at` while the order is pending.
+    /// `None` if none of the shipment methods provide an estimate.
+    async fn estimated_delivery_date<'a>(&self, ctx: &Context<'a>) -> Result<Option<DateTime>> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<ShipmentMethod> =
+            db_client.collection::<ShipmentMethod>(SHIPMENT_METHODS);
+        let shipment_method_ids: Vec<Uuid> = self
+            .internal_order_items
+            .iter()
+            .map(|order_item| order_item.shipment_method._id)
+            .collect();
+        let shipment_methods = query_objects(&collection, &shipment_method_ids).await?;
+        let maybe_estimated_delivery_days = shipment_methods
+            .values()
+            .filter_map(|shipment_method| shipment_method.estimated_delivery_days)
+            .max();
+        let estimated_delivery_date =
+            maybe_estimated_delivery_days.map(|estimated_delivery_days| {
+                let start = self.placed_at.unwrap_or(self.created_at).to_chrono();
+                let delivery_duration = chrono::Duration::days(i64::from(estimated_delivery_days));
+                DateTime::from_chrono(start + delivery_duration)
+            });
+        Ok(estimated_delivery_date)
+    }
+
     /// Retrieves order items.
     async fn order_items(
         &self,
-        #[graphql(desc = "Describes that the `first` N order items should be retrieved.")]
+        #[graphql(
+            desc = "Describes that the `first` N order items should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
         first: Option<usize>,
         #[graphql(desc = "Describes how many order items should be skipped at the beginning.")]
         skip: Option<usize>,
@@ -61,7 +364,7 @@ impl Order {
         sort_order_items(&mut order_items, order_by);
         let total_count = order_items.len();
         let definitely_skip = skip.unwrap_or(0);
-        let definitely_first = first.unwrap_or(usize::MAX);
+        let definitely_first = clamp_page_size(first);
         let order_items_part: Vec<OrderItem> = order_items
             .into_iter()
             .skip(definitely_skip)
@@ -74,6 +377,65 @@ impl Order {
             total_count: total_count as u64,
         })
     }
+
+    /// Timeline of the order, combining its status transitions and compensations sorted by timestamp ascending.
+    ///
+    /// The only shipment and payment events the service consumes, `shipment/shipment/creation-failed` and `payment/payment/failed`, are contained as the compensations and status transitions they cause.
+    /// Other shipment and payment progress, e.g. shipment status updates, is neither consumed nor persisted by the service and is therefore omitted, it is available from the shipment and payment services.
+    async fn timeline<'a>(&self, ctx: &Context<'a>) -> Result<Vec<OrderTimelineEntry>> {
+        authorize_user(ctx, Some(self.user._id))?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<OrderCompensation> =
+            db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS);
+        let order_compensations = query_order_compensations_of_order(&collection, self._id).await?;
+        Ok(build_order_timeline(
+            &self.status_history,
+            order_compensations,
+        ))
+    }
+
+    /// Each distinct coupon applied to the order items of the order, empty if no coupons were used.
+    async fn applied_coupons(&self) -> Vec<AppliedCoupon> {
+        collect_applied_coupons(&self.internal_order_items)
+    }
+
+    /// Retrieves all discounts applied to the order items of the order, deduplicated by discount UUID.
+    async fn discounts(
+        &self,
+        #[graphql(
+            desc = "Describes that the `first` N discounts should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
+        first: Option<usize>,
+        #[graphql(
+            desc = "Describes how many discounts should be skipped at the beginning."
+        )]
+        skip: Option<usize>,
+        #[graphql(desc = "Specifies the order in which discounts are retrieved.")] order_by: Option<
+            CommonOrderInput,
+        >,
+    ) -> Result<DiscountConnection> {
+        let discounts: BTreeSet<Discount> = self
+            .internal_order_items
+            .iter()
+            .flat_map(|order_item| order_item.internal_discounts.iter().cloned())
+            .collect();
+        let mut discounts: Vec<Discount> = discounts.into_iter().collect();
+        sort_discounts(&mut discounts, order_by);
+        let total_count = discounts.len();
+        let definitely_skip = skip.unwrap_or(0);
+        let definitely_first = clamp_page_size(first);
+        let discounts_part: Vec<Discount> = discounts
+            .into_iter()
+            .skip(definitely_skip)
+            .take(definitely_first)
+            .collect();
+        let has_next_page = total_count > discounts_part.len() + definitely_skip;
+        Ok(DiscountConnection {
+            nodes: discounts_part,
+            has_next_page,
+            total_count: total_count as u64,
+        })
+    }
 }
 
 /// Describes if order is placed, or yet pending. An order can be rejected during its lifetime.
@@ -104,6 +466,31 @@ impl From<OrderStatus> for Bson {
     }
 }
 
+/// Describes a status transition of an order.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy, SimpleObject)]
+pub struct StatusHistoryEntry {
+    /// Status the order transitioned to.
+    pub status: OrderStatus,
+    /// Timestamp of the status transition.
+    pub at: DateTime,
+}
+
+impl StatusHistoryEntry {
+    /// Constructs a status history entry of a status transition.
+    ///
+    /// * `status` - Status the order transitioned to.
+    /// * `at` - Timestamp of the status transition.
+    pub fn new(status: OrderStatus, at: DateTime) -> Self {
+        Self { status, at }
+    }
+}
+
+impl From<StatusHistoryEntry> for Bson {
+    fn from(value: StatusHistoryEntry) -> Self {
+        Bson::Document(doc!("status": value.status, "at": value.at))
+    }
+}
+
 /// Describes the reason why an order was rejected, in case of rejection: `OrderStatus::Rejected`.
 #[derive(Debug, Enum, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -112,6 +499,27 @@ pub enum RejectionReason {
     InvalidOrderData,
     /// The inventory service was not able to reserve inventory items according to the order.
     InventoryReservationFailed,
+    /// The order was `OrderStatus::Pending` for longer than the pending timeout when it was placed.
+    PendingTimeoutExceeded,
+    /// The payment service was not able to process the payment of the order.
+    PaymentFailed,
+}
+
+impl RejectionReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RejectionReason::InvalidOrderData => "INVALID_ORDER_DATA",
+            RejectionReason::InventoryReservationFailed => "INVENTORY_RESERVATION_FAILED",
+            RejectionReason::PendingTimeoutExceeded => "PENDING_TIMEOUT_EXCEEDED",
+            RejectionReason::PaymentFailed => "PAYMENT_FAILED",
+        }
+    }
+}
+
+impl From<RejectionReason> for Bson {
+    fn from(value: RejectionReason) -> Self {
+        Bson::from(value.as_str())
+    }
 }
 
 impl From<Order> for Uuid {
@@ -125,13 +533,162 @@ impl From<Order> for Uuid {
 /// * `order_items` - Vector of order items to sort.
 /// * `order_by` - Specifies order of sorted result.
 fn sort_order_items(order_items: &mut Vec<OrderItem>, order_by: Option<CommonOrderInput>) {
-    let comparator: fn(&OrderItem, &OrderItem) -> bool =
-        match order_by.unwrap_or_default().direction.unwrap_or_default() {
-            OrderDirection::Asc => |x, y| x < y,
-            OrderDirection::Desc => |x, y| x > y,
+    let order_by = order_by.unwrap_or_default();
+    let comparator: fn(&OrderItem, &OrderItem) -> Ordering =
+        match order_by.field.unwrap_or_default() {
+            CommonOrderField::Id => |x, y| x.cmp(y),
+            CommonOrderField::CreatedAt => |x, y| x.created_at.cmp(&y.created_at).then(x.cmp(y)),
         };
-    order_items.sort_by(|x, y| match comparator(x, y) {
-        true => Ordering::Less,
-        false => Ordering::Greater,
+    order_items.sort_by(|x, y| match order_by.direction.unwrap_or_default() {
+        OrderDirection::Asc => comparator(x, y),
+        OrderDirection::Desc => comparator(y, x),
     });
 }
+
+/// Sums up the amounts of order compensations, which is the amount refunded for their order.
+///
+/// * `order_compensations` - Order compensations of an order.
+fn sum_refunded_amount(order_compensations: &[OrderCompensation]) -> u64 {
+    order_compensations
+        .iter()
+        .map(|order_compensation| order_compensation.amount_to_compensate)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use bson::{doc, Document};
+
+    use super::*;
+    use crate::test_fixtures::{build_order, build_order_item};
+
+    /// Builds a placed gift order, which sets all optional attributes.
+    fn build_placed_gift_order() -> Order {
+        let mut order = build_order(vec![build_order_item(bson::Uuid::new(), 1190, 2)], 499);
+        let placed_at = DateTime::from_millis(1_700_000_000_000);
+        order.order_status = OrderStatus::Placed;
+        order.placed_at = Some(placed_at);
+        order.is_gift = true;
+        order.gift_recipient_address = Some(GiftRecipientAddress {
+            name: "Erika Mustermann".to_string(),
+            street: "Heidestraße 17".to_string(),
+            postal_code: "51147".to_string(),
+            city: "Köln".to_string(),
+            country: "DE".to_string(),
+        });
+        order.vat_number = Some("DE123456789".to_string());
+        order.customer_note = Some("Please ring twice.".to_string());
+        order
+            .status_history
+            .push(StatusHistoryEntry::new(OrderStatus::Placed, placed_at));
+        order.event_published = true;
+        order.payment_authorization_provided = true;
+        order.internal_order_items[0].applied_coupon_ids = vec![bson::Uuid::new()];
+        order
+    }
+
+    #[test]
+    fn order_round_trips_through_bson_and_json() {
+        let order = build_placed_gift_order();
+        let document = bson::to_document(&order).unwrap();
+        assert_eq!(bson::from_document::<Order>(document).unwrap(), order);
+        let json = serde_json::to_string(&order).unwrap();
+        assert_eq!(serde_json::from_str::<Order>(&json).unwrap(), order);
+    }
+
+    #[test]
+    fn legacy_order_document_without_defaulted_attributes_is_deserialized() {
+        let order = build_placed_gift_order();
+        let mut document = bson::to_document(&order).unwrap();
+        for attribute in [
+            "last_updated_at",
+            "is_gift",
+            "gift_recipient_address",
+            "shipping_total",
+            "customer_note",
+            "status_history",
+            "event_published",
+            "payment_authorization_provided",
+            "currency_conversion",
+        ] {
+            document.remove(attribute);
+        }
+        let order_items: Vec<Document> = document
+            .get_array("internal_order_items")
+            .unwrap()
+            .iter()
+            .map(|order_item| {
+                let mut order_item = order_item.as_document().unwrap().clone();
+                order_item.remove("compensatable_amount_includes_count");
+                order_item.remove("applied_coupon_ids");
+                order_item
+            })
+            .collect();
+        document.insert("internal_order_items", order_items);
+        let legacy_order: Order = bson::from_document(document).unwrap();
+        assert_eq!(
+            legacy_order,
+            Order {
+                last_updated_at: None,
+                is_gift: false,
+                gift_recipient_address: None,
+                shipping_total: 0,
+                customer_note: None,
+                status_history: vec![],
+                event_published: false,
+                payment_authorization_provided: false,
+                currency_conversion: None,
+                internal_order_items: order
+                    .internal_order_items
+                    .iter()
+                    .cloned()
+                    .map(|order_item| OrderItem {
+                        compensatable_amount_includes_count: false,
+                        applied_coupon_ids: vec![],
+                        ..order_item
+                    })
+                    .collect(),
+                ..order
+            }
+        );
+        assert!(bson::from_document::<Order>(doc! {"_id": bson::Uuid::new()}).is_err());
+    }
+
+    #[test]
+    fn grand_total_includes_shipping_total() {
+        let order = build_order(
+            vec![
+                build_order_item(bson::Uuid::new(), 1190, 2),
+                build_order_item(bson::Uuid::new(), 595, 1),
+            ],
+            499,
+        );
+        assert_eq!(order.compensatable_order_amount, 2975);
+        assert_eq!(order.calculate_grand_total(), 3474);
+        assert_eq!(
+            order.calculate_items_subtotal() + order.calculate_tax_amount() + order.shipping_total,
+            order.calculate_grand_total()
+        );
+    }
+
+    #[test]
+    fn net_amount_is_grand_total_minus_refunded_amount() {
+        let order = build_order(vec![build_order_item(bson::Uuid::new(), 1000, 2)], 500);
+        let build_order_compensation = |amount_to_compensate: u64| OrderCompensation {
+            _id: bson::Uuid::new(),
+            order_id: order._id,
+            order_item_ids: vec![order.internal_order_items[0]._id],
+            triggered_at: DateTime::now(),
+            amount_to_compensate,
+            reason: None,
+            event_published: true,
+        };
+        let refunded_amount =
+            sum_refunded_amount(&[build_order_compensation(700), build_order_compensation(300)]);
+        assert_eq!(refunded_amount, 1000);
+        assert_eq!(order.calculate_net_amount(refunded_amount), 1500);
+        assert_eq!(order.calculate_net_amount(0), order.calculate_grand_total());
+        assert_eq!(order.calculate_net_amount(3000), 0);
+        assert_eq!(sum_refunded_amount(&[]), 0);
+    }
+}
@@ -0,0 +1,14 @@
+use async_graphql::SimpleObject;
+
+use super::order::Order;
+
+/// Result of creating a single order of a batch of orders.
+///
+/// Contains either the created order or the error that prevented its creation.
+#[derive(Debug, SimpleObject)]
+pub struct OrderCreationResult {
+    /// Created order, `None` if the creation failed.
+    pub order: Option<Order>,
+    /// Error message describing why the creation failed, `None` if the order was created.
+    pub error: Option<String>,
+}
@@ -36,6 +36,10 @@ pub enum OrderOrderField {
     Name,
     /// Orders by "created_at".
     CreatedAt,
+    /// Orders by "placed_at".
+    ///
+    /// Pending orders have no placement timestamp and are sorted as nulls.
+    PlacedAt,
     /// Orders by "last_updated_at".
     LastUpdatedAt,
 }
@@ -47,6 +51,7 @@ impl OrderOrderField {
             OrderOrderField::UserId => "user._id",
             OrderOrderField::Name => "name",
             OrderOrderField::CreatedAt => "created_at",
+            OrderOrderField::PlacedAt => "placed_at",
             OrderOrderField::LastUpdatedAt => "last_updated_at",
         }
     }
@@ -77,18 +82,21 @@ impl Default for OrderOrderInput {
 }
 
 /// Describes the fields that a foreign types can be ordered by.
-///
-/// Only the id valid at the moment.
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub enum CommonOrderField {
     /// Orders by "id".
     Id,
+    /// Orders by "created_at", ties are ordered by "id".
+    ///
+    /// Types without a creation timestamp are ordered by "id".
+    CreatedAt,
 }
 
 impl CommonOrderField {
     pub fn as_str(&self) -> &'static str {
         match self {
             CommonOrderField::Id => "_id",
+            CommonOrderField::CreatedAt => "created_at",
         }
     }
 }
@@ -0,0 +1,155 @@
+use async_graphql::{Result, SimpleObject};
+use bson::Uuid;
+
+use crate::rounding_strategy::RoundingStrategy;
+
+use super::{
+    money::Money,
+    order::Order,
+    order_item::{calculate_contained_tax_amount, OrderItem},
+};
+
+/// Comparison of an amount recomputed from the stored order items to the persisted amount.
+#[derive(Debug, SimpleObject)]
+pub struct AmountComparison {
+    /// Amount recomputed from the stored prices, discounts and tax rates.
+    pub expected: Money,
+    /// Amount persisted in the order.
+    pub stored: Money,
+    /// Whether the recomputed amount equals the persisted amount.
+    pub is_consistent: bool,
+}
+
+impl AmountComparison {
+    /// Constructs the comparison of an expected and a stored amount in minor units.
+    ///
+    /// * `expected` - Recomputed amount in minor units.
+    /// * `stored` - Persisted amount in minor units.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    fn new(expected: u64, stored: u64, currency: &str) -> Result<Self> {
+        Ok(Self {
+            expected: Money::from_minor_units(expected, currency)?,
+            stored: Money::from_minor_units(stored, currency)?,
+            is_consistent: expected == stored,
+        })
+    }
+}
+
+/// Integrity of an order item, comparing its recomputed amounts to the persisted ones.
+#[derive(Debug, SimpleObject)]
+pub struct OrderItemIntegrity {
+    /// UUID of the order item.
+    pub order_item_id: Uuid,
+    /// Compensatable amount recomputed from the product variant version price, the discounts and the count.
+    ///
+    /// Legacy order items storing the amount of a single product item are recomputed with a count of `1`.
+    pub compensatable_amount: AmountComparison,
+    /// Tax contained in the compensatable amount.
+    pub tax_amount: AmountComparison,
+}
+
+impl OrderItemIntegrity {
+    /// Constructs the integrity of an order item.
+    ///
+    /// * `order_item` - Stored order item to verify.
+    /// * `expected_compensatable_amount` - Compensatable amount recomputed by `OrderItem::calculate_expected_compensatable_amount`.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    fn new(
+        order_item: &OrderItem,
+        expected_compensatable_amount: u64,
+        currency: &str,
+    ) -> Result<Self> {
+        let expected_tax_amount = calculate_contained_tax_amount(
+            expected_compensatable_amount,
+            order_item.tax_rate_version.rate_basis_points(),
+        );
+        Ok(Self {
+            order_item_id: order_item._id,
+            compensatable_amount: AmountComparison::new(
+                expected_compensatable_amount,
+                order_item.compensatable_amount,
+                currency,
+            )?,
+            tax_amount: AmountComparison::new(
+                expected_tax_amount,
+                order_item.calculate_tax_amount(),
+                currency,
+            )?,
+        })
+    }
+}
+
+/// Integrity report of an order for financial auditing.
+///
+/// Amounts are recomputed with the current rounding strategy and discounted price floor, which may differ from the configuration at order creation.
+/// Shipment fees are not stored per order item, the shipping total can therefore only be reported, not recomputed.
+#[derive(Debug, SimpleObject)]
+pub struct OrderIntegrityReport {
+    /// UUID of the order.
+    pub order_id: Uuid,
+    /// Whether all recomputed amounts equal the persisted amounts.
+    pub is_consistent: bool,
+    /// Integrity of each order item of the order.
+    pub order_items: Vec<OrderItemIntegrity>,
+    /// Compensatable order amount recomputed as the sum of the recomputed order item amounts.
+    pub compensatable_order_amount: AmountComparison,
+    /// Total tax contained in the order.
+    pub tax_amount: AmountComparison,
+    /// Persisted total shipment fees of the order.
+    pub shipping_total: Money,
+}
+
+impl OrderIntegrityReport {
+    /// Constructs the integrity report of an order.
+    ///
+    /// * `order` - Stored order to verify.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    /// * `rounding_strategy` - Strategy to round the recomputed compensatable amounts to minor units.
+    pub fn new(order: &Order, currency: &str, rounding_strategy: RoundingStrategy) -> Result<Self> {
+        let expected_compensatable_amounts: Vec<u64> = order
+            .internal_order_items
+            .iter()
+            .map(|order_item| order_item.calculate_expected_compensatable_amount(rounding_strategy))
+            .collect();
+        let expected_compensatable_order_amount = expected_compensatable_amounts.iter().sum();
+        let expected_tax_amount = order
+            .internal_order_items
+            .iter()
+            .zip(expected_compensatable_amounts.iter())
+            .map(|(order_item, expected_compensatable_amount)| {
+                calculate_contained_tax_amount(
+                    *expected_compensatable_amount,
+                    order_item.tax_rate_version.rate_basis_points(),
+                )
+            })
+            .sum();
+        let order_items = order
+            .internal_order_items
+            .iter()
+            .zip(expected_compensatable_amounts.iter())
+            .map(|(order_item, expected_compensatable_amount)| {
+                OrderItemIntegrity::new(order_item, *expected_compensatable_amount, currency)
+            })
+            .collect::<Result<Vec<OrderItemIntegrity>>>()?;
+        let compensatable_order_amount = AmountComparison::new(
+            expected_compensatable_order_amount,
+            order.compensatable_order_amount,
+            currency,
+        )?;
+        let tax_amount =
+            AmountComparison::new(expected_tax_amount, order.calculate_tax_amount(), currency)?;
+        let is_consistent = compensatable_order_amount.is_consistent
+            && tax_amount.is_consistent
+            && order_items.iter().all(|order_item| {
+                order_item.compensatable_amount.is_consistent && order_item.tax_amount.is_consistent
+            });
+        Ok(Self {
+            order_id: order._id,
+            is_consistent,
+            order_items,
+            compensatable_order_amount,
+            tax_amount,
+            shipping_total: Money::from_minor_units(order.shipping_total, currency)?,
+        })
+    }
+}
@@ -1,17 +1,28 @@
-use std::{cmp::Ordering, collections::BTreeSet};
+use std::{cmp::Ordering, collections::BTreeSet, env};
 
-use async_graphql::{ComplexObject, Result, SimpleObject};
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
 use bson::{DateTime, Uuid};
+use mongodb::Database;
 use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    collection_names::{ORDERS, PRODUCT_VARIANTS},
+    graphql::query::{query_object, query_optional_order_from_order_item_id},
+    rounding_strategy::RoundingStrategy,
+    store_config::StoreConfig,
+};
 
 use super::{
     super::mutation_input_structs::OrderItemInput,
-    connection::discount_connection::DiscountConnection,
+    connection::{base_connection::clamp_page_size, discount_connection::DiscountConnection},
     foreign_types::{
         Discount, ProductVariant, ProductVariantVersion, ShipmentMethod, ShoppingCartItem,
-        TaxRateVersion,
+        TaxRateVersion, BASIS_POINTS_PER_UNIT,
     },
-    order_datatypes::{CommonOrderInput, OrderDirection},
+    money::Money,
+    order::{Order, OrderStatus},
+    order_datatypes::{CommonOrderField, CommonOrderInput, OrderDirection},
 };
 
 /// Describes an order item of an order.
@@ -23,6 +34,8 @@ pub struct OrderItem {
     /// Timestamp when order item was created.
     pub created_at: DateTime,
     /// Product variant associated with order item.
+    ///
+    /// Snapshot of the product variant at order item creation, use `product_currently_available` for its current state.
     pub product_variant: ProductVariant,
     /// Product variant version associated with order item.
     pub product_variant_version: ProductVariantVersion,
@@ -33,12 +46,34 @@ pub struct OrderItem {
     /// Specifies the quantity of the order item.
     pub count: u64,
     /// Total cost of product item, which can also be refunded.
+    #[graphql(skip)]
     pub compensatable_amount: u64,
+    /// Whether `compensatable_amount` is the total of all product items of the order item.
+    ///
+    /// Order items stored before the compensatable amount included the quantity contain the amount of a single product item.
+    /// Recalculations of such legacy order items therefore use a quantity of `1`, until the order item is recalculated by a mutation.
+    #[graphql(skip)]
+    #[serde(default)]
+    pub compensatable_amount_includes_count: bool,
     /// Shipment method of order item.
     pub shipment_method: ShipmentMethod,
     /// The internal vector consisting of discounts.
     #[graphql(skip)]
     pub internal_discounts: BTreeSet<Discount>,
+    /// UUIDs of coupons of the order item input, which resulted in the discounts of the order item.
+    #[serde(default)]
+    pub applied_coupon_ids: Vec<Uuid>,
+}
+
+/// Inputs determining the price of an order item besides its product variant version and quantity.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderItemPricing<'a> {
+    /// Current tax rate version of the tax rate of the product variant version.
+    pub tax_rate_version: &'a TaxRateVersion,
+    /// Discounts applicable to the order item.
+    pub internal_discounts: &'a BTreeSet<Discount>,
+    /// Strategy to round the discounted price to minor units.
+    pub rounding_strategy: RoundingStrategy,
 }
 
 impl OrderItem {
@@ -49,19 +84,28 @@ impl OrderItem {
         order_item_input: &OrderItemInput,
         product_variant: &ProductVariant,
         product_variant_version: &ProductVariantVersion,
-        tax_rate_version: &TaxRateVersion,
+        pricing: OrderItemPricing,
         count: u64,
-        internal_discounts: &BTreeSet<Discount>,
         current_timestamp: DateTime,
     ) -> Self {
-        let compensatable_amount =
-            calculate_compensatable_amount(product_variant_version, &internal_discounts);
+        let OrderItemPricing {
+            tax_rate_version,
+            internal_discounts,
+            rounding_strategy,
+        } = pricing;
+        let compensatable_amount = calculate_compensatable_amount(
+            product_variant_version,
+            internal_discounts,
+            count,
+            rounding_strategy,
+        );
         let shopping_cart_item = ShoppingCartItem {
             _id: order_item_input.shopping_cart_item_id,
         };
-        let shipment_method = ShipmentMethod {
-            _id: order_item_input.shipment_method_id,
-        };
+        let shipment_method = ShipmentMethod::from(order_item_input.shipment_method_id);
+        let mut applied_coupon_ids: Vec<Uuid> =
+            order_item_input.coupon_ids.iter().cloned().collect();
+        applied_coupon_ids.sort();
         Self {
             _id: Uuid::new(),
             created_at: current_timestamp,
@@ -71,18 +115,136 @@ impl OrderItem {
             shopping_cart_item,
             count,
             compensatable_amount,
+            compensatable_amount_includes_count: true,
             shipment_method,
             internal_discounts: internal_discounts.clone(),
+            applied_coupon_ids,
         }
     }
+
+    /// Calculates the end of the return window from the placement of the order containing the order item.
+    ///
+    /// Returns `None` if the order is not `OrderStatus::Placed` or not persisted, e.g. for order items of order previews.
+    ///
+    /// * `ctx` - GraphQL context containing the database client.
+    async fn calculate_return_window_end<'a>(&self, ctx: &Context<'a>) -> Result<Option<DateTime>> {
+        let db_client = ctx.data::<Database>()?;
+        let collection = db_client.collection::<Order>(ORDERS);
+        let order = query_optional_order_from_order_item_id(&collection, self._id).await?;
+        let return_window_end = order.and_then(|order| {
+            calculate_return_window_end_of_order(
+                &order,
+                chrono::Duration::days(return_window_days()),
+            )
+        });
+        Ok(return_window_end)
+    }
+
+    /// Recalculates the compensatable amount of the order item from its product variant version price, its discounts and its quantity.
+    ///
+    /// Legacy order items are migrated, the recalculated compensatable amount includes the quantity.
+    ///
+    /// * `rounding_strategy` - Strategy to round the discounted price to minor units.
+    pub fn recalculate_compensatable_amount(&mut self, rounding_strategy: RoundingStrategy) {
+        self.compensatable_amount = calculate_compensatable_amount(
+            &self.product_variant_version,
+            &self.internal_discounts,
+            self.count,
+            rounding_strategy,
+        );
+        self.compensatable_amount_includes_count = true;
+    }
+
+    /// Calculates the compensatable amount the order item is expected to store, without migrating legacy order items.
+    ///
+    /// Legacy order items, which store the amount of a single product item, are calculated with a quantity of `1`.
+    ///
+    /// * `rounding_strategy` - Strategy to round the discounted price to minor units.
+    pub fn calculate_expected_compensatable_amount(
+        &self,
+        rounding_strategy: RoundingStrategy,
+    ) -> u64 {
+        let count = match self.compensatable_amount_includes_count {
+            true => self.count,
+            false => 1,
+        };
+        calculate_compensatable_amount(
+            &self.product_variant_version,
+            &self.internal_discounts,
+            count,
+            rounding_strategy,
+        )
+    }
+
+    /// Calculates the tax contained in the compensatable amount of the order item.
+    ///
+    /// Product variant version prices are gross prices, the tax is therefore contained and not added on top.
+    pub fn calculate_tax_amount(&self) -> u64 {
+        calculate_contained_tax_amount(
+            self.compensatable_amount,
+            self.tax_rate_version.rate_basis_points(),
+        )
+    }
 }
 
 #[ComplexObject]
 impl OrderItem {
+    /// Total cost of product item, which can also be refunded.
+    async fn compensatable_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.compensatable_amount, &store_config.currency)
+    }
+
+    /// Price of a single product item before discounts, based on the product variant version of the order item.
+    async fn unit_price<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(
+            self.product_variant_version.price.into(),
+            &store_config.currency,
+        )
+    }
+
+    /// Tax contained in the compensatable amount, based on the tax rate version of the order item.
+    async fn tax_amount<'a>(&self, ctx: &Context<'a>) -> Result<Money> {
+        let store_config = ctx.data::<StoreConfig>()?;
+        Money::from_minor_units(self.calculate_tax_amount(), &store_config.currency)
+    }
+
+    /// Whether the product variant of the order item is currently publicly visible, e.g. to disable a "buy again" action.
+    ///
+    /// Resolved against the latest stored product variant, in contrast to the snapshot stored on the order item.
+    async fn product_currently_available<'a>(&self, ctx: &Context<'a>) -> Result<bool> {
+        let db_client = ctx.data::<Database>()?;
+        let collection = db_client.collection::<ProductVariant>(PRODUCT_VARIANTS);
+        let product_variant = query_object(&collection, self.product_variant._id).await?;
+        if !product_variant.is_publicly_visible {
+            info!(
+                "Product variant of UUID: `{}` of order item of UUID: `{}` is no longer publicly visible.",
+                product_variant._id, self._id
+            );
+        }
+        Ok(product_variant.is_publicly_visible)
+    }
+
+    /// Timestamp when the return window of the order item ends, `None` while the order is not `OrderStatus::Placed` or for order previews.
+    ///
+    /// The return window starts at the placement of the order and lasts `$RETURN_WINDOW_DAYS` days.
+    async fn return_window_ends_at<'a>(&self, ctx: &Context<'a>) -> Result<Option<DateTime>> {
+        self.calculate_return_window_end(ctx).await
+    }
+
+    /// Whether the order item can still be returned, i.e. the return window has not ended yet.
+    async fn is_returnable<'a>(&self, ctx: &Context<'a>) -> Result<bool> {
+        let return_window_end = self.calculate_return_window_end(ctx).await?;
+        Ok(is_within_return_window(return_window_end, DateTime::now()))
+    }
+
     /// Retrieves discounts.
     async fn discounts(
         &self,
-        #[graphql(desc = "Describes that the `first` N discounts should be retrieved.")]
+        #[graphql(
+            desc = "Describes that the `first` N discounts should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
         first: Option<usize>,
         #[graphql(
             desc = "Describes how many discounts should be skipped at the beginning."
@@ -96,7 +258,7 @@ impl OrderItem {
         sort_discounts(&mut discounts, order_by);
         let total_count = discounts.len();
         let definitely_skip = skip.unwrap_or(0);
-        let definitely_first = first.unwrap_or(usize::MAX);
+        let definitely_first = clamp_page_size(first);
         let discounts_part: Vec<Discount> = discounts
             .into_iter()
             .skip(definitely_skip)
@@ -123,26 +285,76 @@ impl Ord for OrderItem {
     }
 }
 
+/// Default length of the return window of order items in days.
+const DEFAULT_RETURN_WINDOW_DAYS: i64 = 14;
+
+/// Length of the return window of order items in days.
+///
+/// Read from `$RETURN_WINDOW_DAYS`, defaults to `DEFAULT_RETURN_WINDOW_DAYS`.
+fn return_window_days() -> i64 {
+    env::var("RETURN_WINDOW_DAYS")
+        .ok()
+        .and_then(|return_window_days| return_window_days.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_RETURN_WINDOW_DAYS)
+}
+
+/// Calculates the end of the return window of the order items of an order, `None` if the order is not `OrderStatus::Placed`.
+///
+/// * `order` - Order containing the order items.
+/// * `return_window` - Length of the return window.
+fn calculate_return_window_end_of_order(
+    order: &Order,
+    return_window: chrono::Duration,
+) -> Option<DateTime> {
+    match order.order_status {
+        OrderStatus::Placed => order
+            .placed_at
+            .map(|placed_at| DateTime::from_chrono(placed_at.to_chrono() + return_window)),
+        _ => None,
+    }
+}
+
+/// Checks if a timestamp is within a return window, the end of the return window is inclusive.
+///
+/// * `return_window_end` - End of the return window, `None` if the order item is not returnable.
+/// * `timestamp` - Timestamp to check.
+fn is_within_return_window(return_window_end: Option<DateTime>, timestamp: DateTime) -> bool {
+    return_window_end
+        .map(|return_window_end| timestamp <= return_window_end)
+        .unwrap_or(false)
+}
+
 /// Sorts vector of discounts according to base order.
 ///
+/// Discounts have no creation timestamp, `CommonOrderField::CreatedAt` therefore orders by id.
+///
 /// * `discounts` - Vector of discounts to sort.
 /// * `order_by` - Specifies order of sorted result.
-fn sort_discounts(discounts: &mut Vec<Discount>, order_by: Option<CommonOrderInput>) {
-    let comparator: fn(&Discount, &Discount) -> bool =
-        match order_by.unwrap_or_default().direction.unwrap_or_default() {
-            OrderDirection::Asc => |x, y| x < y,
-            OrderDirection::Desc => |x, y| x > y,
-        };
-    discounts.sort_by(|x, y| match comparator(x, y) {
-        true => Ordering::Less,
-        false => Ordering::Greater,
+pub fn sort_discounts(discounts: &mut [Discount], order_by: Option<CommonOrderInput>) {
+    let order_by = order_by.unwrap_or_default();
+    let comparator: fn(&Discount, &Discount) -> Ordering = match order_by.field.unwrap_or_default()
+    {
+        CommonOrderField::Id | CommonOrderField::CreatedAt => |x, y| x.cmp(y),
+    };
+    discounts.sort_by(|x, y| match order_by.direction.unwrap_or_default() {
+        OrderDirection::Asc => comparator(x, y),
+        OrderDirection::Desc => comparator(y, x),
     });
 }
 
 /// Applies fees and discounts to calculate the compensatable amount of an order item.
-fn calculate_compensatable_amount(
+///
+/// The discounted price of a single product item is clamped to the discounted price floor, rounded to minor units according to the rounding strategy and multiplied by the quantity of the order item.
+///
+/// * `product_variant_version` - Product variant version containing the undiscounted price of a single product item.
+/// * `internal_discounts` - Discounts applied to the price of a single product item.
+/// * `count` - Quantity of the order item.
+/// * `rounding_strategy` - Strategy to round the discounted price to minor units.
+pub fn calculate_compensatable_amount(
     product_variant_version: &ProductVariantVersion,
     internal_discounts: &BTreeSet<Discount>,
+    count: u64,
+    rounding_strategy: RoundingStrategy,
 ) -> u64 {
     let undiscounted_price = product_variant_version.price as f64;
     let discounted_price = internal_discounts
@@ -150,6 +362,185 @@ fn calculate_compensatable_amount(
         .fold(undiscounted_price, |prev_price, discount| {
             prev_price * discount.discount
         });
-    let total_price = discounted_price as u64;
+    let floor_price = calculate_discounted_price_floor(undiscounted_price);
+    let total_price = rounding_strategy.round(discounted_price.max(floor_price)) * count;
     total_price
 }
+
+/// Calculates the price, which discounts can not reduce the price of a single product item below.
+///
+/// The floor is the larger of the fraction `$MIN_DISCOUNTED_PRICE_FRACTION` of the undiscounted price, e.g. `0.5`, and the absolute amount `$MIN_DISCOUNTED_PRICE` in minor units.
+/// The floor never exceeds the undiscounted price. Defaults to no floor if both are unset.
+///
+/// * `undiscounted_price` - Price of a single product item before discounts.
+fn calculate_discounted_price_floor(undiscounted_price: f64) -> f64 {
+    let read_env_f64 = |key: &str| {
+        env::var(key)
+            .ok()
+            .and_then(|value| value.parse::<f64>().ok())
+            .unwrap_or(0.0)
+    };
+    calculate_discounted_price_floor_of(
+        undiscounted_price,
+        read_env_f64("MIN_DISCOUNTED_PRICE_FRACTION"),
+        read_env_f64("MIN_DISCOUNTED_PRICE"),
+    )
+}
+
+/// Calculates the discounted price floor of a single product item from a fraction and an absolute amount.
+///
+/// * `undiscounted_price` - Price of a single product item before discounts.
+/// * `min_fraction` - Fraction of the undiscounted price, which the floor is at least.
+/// * `min_price` - Absolute amount in minor units, which the floor is at least.
+fn calculate_discounted_price_floor_of(
+    undiscounted_price: f64,
+    min_fraction: f64,
+    min_price: f64,
+) -> f64 {
+    (undiscounted_price * min_fraction)
+        .max(min_price)
+        .min(undiscounted_price)
+}
+
+/// Calculates the tax contained in a gross amount, rounded half up to minor units.
+///
+/// Uses integer arithmetic only, so tax amounts are exactly reproducible for equal inputs.
+///
+/// * `gross_amount` - Amount including tax.
+/// * `rate_basis_points` - Tax rate in basis points, e.g. `1900` for 19%.
+pub fn calculate_contained_tax_amount(gross_amount: u64, rate_basis_points: u64) -> u64 {
+    let gross_amount = u128::from(gross_amount);
+    let divisor = u128::from(BASIS_POINTS_PER_UNIT + rate_basis_points);
+    let net_amount =
+        (2 * gross_amount * u128::from(BASIS_POINTS_PER_UNIT) + divisor) / (2 * divisor);
+    (gross_amount - net_amount) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{build_order, build_order_item};
+
+    /// Builds a product variant version with a price of a single product item.
+    fn build_product_variant_version(price: u32) -> ProductVariantVersion {
+        ProductVariantVersion {
+            _id: Uuid::new(),
+            price,
+            tax_rate_id: Uuid::new(),
+        }
+    }
+
+    #[test]
+    fn discounted_compensatable_amount_is_rounded_instead_of_truncated() {
+        let discounts = BTreeSet::from([Discount {
+            _id: Uuid::new(),
+            discount: 0.9,
+        }]);
+        let product_variant_version = build_product_variant_version(1001);
+        assert_eq!(
+            calculate_compensatable_amount(
+                &product_variant_version,
+                &discounts,
+                2,
+                RoundingStrategy::HalfUp
+            ),
+            1802
+        );
+        assert_eq!(
+            calculate_compensatable_amount(
+                &product_variant_version,
+                &discounts,
+                2,
+                RoundingStrategy::Floor
+            ),
+            1800
+        );
+    }
+
+    #[test]
+    fn compensatable_amount_without_discounts_is_price_times_count() {
+        let product_variant_version = build_product_variant_version(999);
+        assert_eq!(
+            calculate_compensatable_amount(
+                &product_variant_version,
+                &BTreeSet::new(),
+                3,
+                RoundingStrategy::HalfUp
+            ),
+            2997
+        );
+    }
+
+    #[test]
+    fn return_window_starts_at_order_placement() {
+        let mut order = build_order(vec![build_order_item(Uuid::new(), 1000, 1)], 0);
+        let return_window = chrono::Duration::days(14);
+        assert_eq!(
+            calculate_return_window_end_of_order(&order, return_window),
+            None
+        );
+        let placed_at = DateTime::from_millis(1_700_000_000_000);
+        order.order_status = OrderStatus::Placed;
+        order.placed_at = Some(placed_at);
+        let return_window_end = calculate_return_window_end_of_order(&order, return_window);
+        assert_eq!(
+            return_window_end,
+            Some(DateTime::from_millis(
+                1_700_000_000_000 + 14 * 24 * 60 * 60 * 1000
+            ))
+        );
+        assert!(is_within_return_window(return_window_end, placed_at));
+        assert!(is_within_return_window(
+            return_window_end,
+            return_window_end.unwrap()
+        ));
+        assert!(!is_within_return_window(
+            return_window_end,
+            DateTime::from_millis(return_window_end.unwrap().timestamp_millis() + 1)
+        ));
+        assert!(!is_within_return_window(None, placed_at));
+    }
+
+    #[test]
+    fn contained_tax_amount_is_rounded_half_up() {
+        assert_eq!(calculate_contained_tax_amount(1190, 1900), 190);
+        assert_eq!(calculate_contained_tax_amount(1000, 1900), 160);
+        assert_eq!(calculate_contained_tax_amount(1000, 0), 0);
+    }
+
+    #[test]
+    fn tax_rates_are_rounded_to_basis_points() {
+        let build_tax_rate_version = |rate: f64| TaxRateVersion {
+            _id: Uuid::new(),
+            rate,
+            version: 1,
+        };
+        assert_eq!(build_tax_rate_version(0.19).rate_basis_points(), 1900);
+        assert_eq!(build_tax_rate_version(0.0725).rate_basis_points(), 725);
+        assert_eq!(build_tax_rate_version(0.1 + 0.2).rate_basis_points(), 3000);
+        assert_eq!(build_tax_rate_version(-0.05).rate_basis_points(), 0);
+    }
+
+    #[test]
+    fn contained_tax_amount_is_exact_for_large_amounts() {
+        assert_eq!(
+            calculate_contained_tax_amount(119_000_000_000_000_000, 1900),
+            19_000_000_000_000_000
+        );
+        assert_eq!(calculate_contained_tax_amount(u64::MAX, 0), 0);
+    }
+
+    #[test]
+    fn discounted_price_floor_is_capped_by_undiscounted_price() {
+        assert_eq!(calculate_discounted_price_floor_of(1000.0, 0.0, 0.0), 0.0);
+        assert_eq!(calculate_discounted_price_floor_of(1000.0, 0.5, 0.0), 500.0);
+        assert_eq!(
+            calculate_discounted_price_floor_of(1000.0, 0.5, 700.0),
+            700.0
+        );
+        assert_eq!(
+            calculate_discounted_price_floor_of(1000.0, 0.1, 1500.0),
+            1000.0
+        );
+    }
+}
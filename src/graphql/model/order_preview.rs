@@ -0,0 +1,44 @@
+use async_graphql::{Result, SimpleObject};
+
+use super::{
+    money::Money,
+    order::{Order, OrderStatus, RejectionReason},
+    order_item::OrderItem,
+};
+
+/// Totals breakdown of an order, which is built like on order creation but not persisted.
+#[derive(Debug, SimpleObject)]
+pub struct OrderPreview {
+    /// The status the order would be created with.
+    pub order_status: OrderStatus,
+    /// The rejection reason if the order would be created with `OrderStatus::Rejected`.
+    pub rejection_reason: Option<RejectionReason>,
+    /// Order items the order would consist of.
+    pub order_items: Vec<OrderItem>,
+    /// Subtotal of the discounted prices of all order items, excluding shipment fees and tax.
+    pub items_subtotal: Money,
+    /// Total shipment fees of all order items.
+    pub shipping_total: Money,
+    /// Total tax contained in the order.
+    pub tax_amount: Money,
+    /// Grand total of the order including shipment fees and tax.
+    pub grand_total: Money,
+}
+
+impl OrderPreview {
+    /// Constructs the preview of an order.
+    ///
+    /// * `order` - Order built from the create order input, which is not persisted.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    pub fn new(order: Order, currency: &str) -> Result<Self> {
+        Ok(Self {
+            order_status: order.order_status,
+            rejection_reason: order.rejection_reason,
+            items_subtotal: Money::from_minor_units(order.calculate_items_subtotal(), currency)?,
+            shipping_total: Money::from_minor_units(order.shipping_total, currency)?,
+            tax_amount: Money::from_minor_units(order.calculate_tax_amount(), currency)?,
+            grand_total: Money::from_minor_units(order.calculate_grand_total(), currency)?,
+            order_items: order.internal_order_items,
+        })
+    }
+}
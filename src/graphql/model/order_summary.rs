@@ -0,0 +1,41 @@
+use async_graphql::{Result, SimpleObject};
+use bson::{DateTime, Uuid};
+
+use super::{
+    money::Money,
+    order::{Order, OrderStatus},
+};
+
+/// Limited view of an order, which is visible without authorization, e.g. for shared order links.
+///
+/// Only contains fields which are considered safe to share: the status, the item count, the total and the placement timestamp.
+/// Addresses, payment information, the VAT number, the customer note and the user are never exposed.
+#[derive(Debug, SimpleObject)]
+pub struct OrderSummary {
+    /// Order UUID.
+    pub id: Uuid,
+    /// The status of the order.
+    pub order_status: OrderStatus,
+    /// Number of order items of the order.
+    pub item_count: u64,
+    /// Grand total of the order including shipment fees, the amount charged when the order is placed.
+    pub total: Money,
+    /// Timestamp of order placement. `None` until order is placed.
+    pub placed_at: Option<DateTime>,
+}
+
+impl OrderSummary {
+    /// Constructs the summary of an order.
+    ///
+    /// * `order` - Order to summarize.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    pub fn new(order: &Order, currency: &str) -> Result<Self> {
+        Ok(Self {
+            id: order._id,
+            order_status: order.order_status,
+            item_count: order.internal_order_items.len() as u64,
+            total: Money::from_minor_units(order.calculate_grand_total(), currency)?,
+            placed_at: order.placed_at,
+        })
+    }
+}
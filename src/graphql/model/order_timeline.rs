@@ -0,0 +1,51 @@
+use async_graphql::Union;
+use bson::DateTime;
+
+use crate::event::order_compensation::OrderCompensation;
+
+use super::order::StatusHistoryEntry;
+
+/// Entry of the timeline of an order.
+///
+/// Payment authorizations and failures are contained as the `OrderStatus::Placed` and `OrderStatus::Rejected` status transitions they cause.
+#[derive(Debug, Clone, Union)]
+pub enum OrderTimelineEntry {
+    /// Status transition of the order.
+    StatusChange(StatusHistoryEntry),
+    /// Compensation of order items of the order, e.g. caused by a failed shipment or payment.
+    Compensation(OrderCompensation),
+}
+
+impl OrderTimelineEntry {
+    /// Timestamp of the timeline entry.
+    pub fn at(&self) -> DateTime {
+        match self {
+            OrderTimelineEntry::StatusChange(status_history_entry) => status_history_entry.at,
+            OrderTimelineEntry::Compensation(order_compensation) => order_compensation.triggered_at,
+        }
+    }
+}
+
+/// Builds the timeline of an order, sorted by timestamp ascending.
+///
+/// Status transitions precede compensations with the same timestamp.
+///
+/// * `status_history` - Status transitions of the order.
+/// * `order_compensations` - Order compensations of the order.
+pub fn build_order_timeline(
+    status_history: &[StatusHistoryEntry],
+    order_compensations: Vec<OrderCompensation>,
+) -> Vec<OrderTimelineEntry> {
+    let mut timeline: Vec<OrderTimelineEntry> = status_history
+        .iter()
+        .copied()
+        .map(OrderTimelineEntry::StatusChange)
+        .chain(
+            order_compensations
+                .into_iter()
+                .map(OrderTimelineEntry::Compensation),
+        )
+        .collect();
+    timeline.sort_by_key(|timeline_entry| timeline_entry.at());
+    timeline
+}
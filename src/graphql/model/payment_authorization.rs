@@ -1,3 +1,5 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
 use super::super::mutation_input_structs::PaymentAuthorizationInput;
@@ -6,13 +8,22 @@ use super::super::mutation_input_structs::PaymentAuthorizationInput;
 ///
 /// This datatype can be extended with different payment authorization formats.
 /// The conversion implementation needs to be adapted accordingly.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+/// It must never be stored in MongoDB, `Debug` redacts its values so it can not be logged.
+#[derive(Serialize, Deserialize, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum PaymentAuthorization {
     /// CVC/CVV number of 3-4 digits.
     CVC(u16),
 }
 
+impl fmt::Debug for PaymentAuthorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaymentAuthorization::CVC(_) => f.write_str("CVC(<redacted>)"),
+        }
+    }
+}
+
 impl From<PaymentAuthorizationInput> for Option<PaymentAuthorization> {
     fn from(value: PaymentAuthorizationInput) -> Self {
         match value.cvc {
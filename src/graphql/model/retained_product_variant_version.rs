@@ -0,0 +1,31 @@
+use async_graphql::{Result, SimpleObject};
+use bson::Uuid;
+
+use super::{foreign_types::ProductVariantVersion, money::Money};
+
+/// Product variant version retained by the order service, including the details historical order items were priced with.
+///
+/// Unlike the `ProductVariantVersion` foreign type, the price and the tax rate are exposed, e.g. for historical invoices.
+#[derive(Debug, SimpleObject)]
+pub struct RetainedProductVariantVersion {
+    /// UUID of the product variant version.
+    pub id: Uuid,
+    /// Price of a single product item of the product variant version.
+    pub price: Money,
+    /// UUID of the tax rate associated with the product variant version.
+    pub tax_rate_id: Uuid,
+}
+
+impl RetainedProductVariantVersion {
+    /// Constructs the retained product variant version from a product variant version.
+    ///
+    /// * `product_variant_version` - Retained product variant version.
+    /// * `currency` - ISO 4217 code of the currency of the store.
+    pub fn new(product_variant_version: &ProductVariantVersion, currency: &str) -> Result<Self> {
+        Ok(Self {
+            id: product_variant_version._id,
+            price: Money::from_minor_units(product_variant_version.price as u64, currency)?,
+            tax_rate_id: product_variant_version.tax_rate_id,
+        })
+    }
+}
@@ -1,21 +1,20 @@
-use async_graphql::{ComplexObject, Context, Error, Result, SimpleObject};
-use bson::{doc, Document, Uuid};
-use mongodb::{options::FindOptions, Collection, Database};
-use mongodb_cursor_pagination::{error::CursorError, FindResult, PaginatedCursor};
+use async_graphql::{ComplexObject, Context, Result, SimpleObject};
+use bson::{doc, Uuid};
+use mongodb::{Collection, Database};
 use serde::{Deserialize, Serialize};
 
-use crate::authorization::authorize_user;
+use crate::{
+    authorization::authorize_user, collection_names::ORDERS, graphql::query::query_order_connection,
+};
 
 use super::{
-    connection::{
-        base_connection::{BaseConnection, FindResultWrapper},
-        order_connection::OrderConnection,
-    },
-    order::Order,
-    order_datatypes::OrderOrderInput,
+    connection::order_connection::OrderConnection, order::Order, order_datatypes::OrderOrderInput,
 };
 
 /// Type of a user owning orders.
+///
+/// Declared with the federation key `id` through the user entity resolver, so the gateway can join contact fields like the email from the user service.
+/// The order service does not store any contact information of users.
 #[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq, Clone, SimpleObject)]
 #[graphql(complex)]
 pub struct User {
@@ -32,9 +31,10 @@ impl User {
     async fn orders<'a>(
         &self,
         ctx: &Context<'a>,
-        #[graphql(desc = "Describes that the `first` N orders should be retrieved.")] first: Option<
-            u32,
-        >,
+        #[graphql(
+            desc = "Describes that the `first` N orders should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
+        first: Option<u32>,
         #[graphql(desc = "Describes how many orders should be skipped at the beginning.")]
         skip: Option<u64>,
         #[graphql(desc = "Specifies the order in which orders are retrieved.")] order_by: Option<
@@ -43,28 +43,9 @@ impl User {
     ) -> Result<OrderConnection> {
         authorize_user(&ctx, Some(self._id))?;
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<Order> = db_client.collection::<Order>("orders");
-        let order_order = order_by.unwrap_or_default();
-        let sorting_doc = doc! {order_order.field.unwrap_or_default().as_str(): i32::from(order_order.direction.unwrap_or_default())};
-        let find_options = FindOptions::builder()
-            .skip(skip)
-            .limit(first.map(|definitely_first| i64::from(definitely_first)))
-            .sort(sorting_doc)
-            .build();
-        let document_collection = collection.clone_with_type::<Document>();
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
         let filter = doc! {"user._id": self._id};
-        let maybe_find_results: Result<FindResult<Order>, CursorError> =
-            PaginatedCursor::new(Some(find_options.clone()), None, None)
-                .find(&document_collection, Some(&filter))
-                .await;
-        match maybe_find_results {
-            Ok(find_results) => {
-                let find_result_wrapper = FindResultWrapper(find_results);
-                let connection = Into::<BaseConnection<Order>>::into(find_result_wrapper);
-                Ok(Into::<OrderConnection>::into(connection))
-            }
-            Err(_) => return Err(Error::new("Retrieving orders failed in MongoDB.")),
-        }
+        query_order_connection(&collection, filter, first, skip, order_by).await
     }
 }
 
@@ -1,42 +1,71 @@
-use async_graphql::{Context, Error, Object, Result};
+use async_graphql::{to_value, Context, Error, ErrorExtensions, Object, Result, Value};
 use bson::Bson;
 use bson::Uuid;
 use futures::TryStreamExt;
 use graphql_client::GraphQLQuery;
-use graphql_client::Response;
+use graphql_client::{QueryBody, Response};
 use mongodb::{
-    bson::{doc, DateTime},
+    bson::{doc, DateTime, Document},
     Collection, Database,
 };
-use serde::Deserialize;
 use serde::Serialize;
+use serde::{de::DeserializeOwned, Deserialize};
 use std::any::type_name;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::env;
 use std::time::Duration;
 use std::time::SystemTime;
+use tracing::{debug, field, info, instrument, warn, Span};
 
 use crate::{
-    authorization::{authorize_user, AuthorizedUserHeader},
-    event::model::order_dto::OrderDTO,
+    authorization::{authorize_admin, authorize_service, authorize_user, AuthorizedUserHeader},
+    collection_names::{
+        COUPONS, ORDERS, ORDER_COMPENSATIONS, PAYMENT_INFORMATIONS, PRODUCT_VARIANTS,
+        SHIPMENT_METHODS, TAX_RATES, USERS,
+    },
+    correlation_id::{CorrelationId, CORRELATION_ID_HEADER},
+    dapr::dapr_url,
+    event::{
+        event_config::EventConfig,
+        model::order_dto::OrderDTO,
+        order_compensation::{refund_order_items, OrderCompensation},
+        webhook::dispatch_to_webhooks,
+    },
+    metrics::increment_rejected_orders,
+    rounding_strategy::RoundingStrategy,
+    store_config::StoreConfig,
 };
 
 use super::{
     model::{
+        currency_conversion::CurrencyConversion,
         foreign_types::{
-            Coupon, Discount, ProductVariant, ProductVariantVersion, ShipmentMethod, TaxRate,
-            TaxRateVersion, UserAddress,
+            Coupon, Discount, PaymentInformation, ProductVariant, ProductVariantVersion,
+            ShipmentMethod, TaxRate, TaxRateVersion, UserAddress,
         },
-        order::{Order, OrderStatus},
-        order_item::OrderItem,
+        gift_recipient_address::GiftRecipientAddress,
+        order::{Order, OrderStatus, RejectionReason, StatusHistoryEntry},
+        order_creation_result::OrderCreationResult,
+        order_item::{OrderItem, OrderItemPricing},
         payment_authorization::PaymentAuthorization,
         user::User,
     },
-    mutation_input_structs::{CreateOrderInput, OrderItemInput, PlaceOrderInput},
-    query::{query_object, query_objects},
+    mutation_input_structs::{
+        ApplyCouponToOrderItemInput, CreateAndPlaceOrderInput, CreateOrderInput,
+        GiftRecipientAddressInput, OrderItemInput, PaymentAuthorizationInput, PlaceOrderInput,
+        RefundOrderItemsInput, UpdateOrderItemCountInput, UpdateOrderPaymentInformationInput,
+    },
+    query::{domain_type_name, query_object, query_objects},
 };
 
-const PENDING_TIMEOUT: Duration = Duration::new(3600, 0);
+pub const PENDING_TIMEOUT: Duration = Duration::new(3600, 0);
+const DEFAULT_CREATE_ORDERS_MAX_BATCH_SIZE: usize = 100;
+/// Maximum amount of characters of a customer note.
+const MAX_CUSTOMER_NOTE_LENGTH: usize = 500;
+/// Default interval between republications of unpublished order created events in seconds.
+const DEFAULT_ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS: u64 = 60;
 
 /// Describes GraphQL order mutations.
 pub struct Mutation;
@@ -44,66 +73,754 @@ pub struct Mutation;
 #[Object]
 impl Mutation {
     /// Creates an order with `OrderStatus::Pending`.
+    ///
+    /// Order items are priced with the current product variant versions at order creation.
+    /// If `$PERSIST_INVENTORY_REJECTED_ORDERS` is set and the order items are unavailable, the order is created with `OrderStatus::Rejected` and `RejectionReason::InventoryReservationFailed` instead.
+    #[instrument(skip_all, fields(user_id = %input.user_id))]
     async fn create_order<'a>(
         &self,
         ctx: &Context<'a>,
         #[graphql(desc = "CreateOrderInput")] input: CreateOrderInput,
     ) -> Result<Order> {
-        authorize_user(&ctx, Some(input.user_id))?;
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<Order> = db_client.collection::<Order>("orders");
-        validate_order_input(db_client, &input).await?;
-        let current_timestamp = DateTime::now();
-        let internal_order_items: Vec<OrderItem> =
-            create_internal_order_items(&ctx, &input, current_timestamp).await?;
-        let shipment_address = UserAddress::from(input.shipment_address_id);
-        let invoice_address = UserAddress::from(input.invoice_address_id);
-        let compensatable_order_amount =
-            calculate_compensatable_order_amount(&internal_order_items);
-        let order = Order {
-            _id: Uuid::new(),
-            user: User::from(input.user_id),
-            created_at: current_timestamp,
-            order_status: OrderStatus::Pending,
-            placed_at: None,
-            rejection_reason: None,
-            internal_order_items,
-            shipment_address,
-            invoice_address,
-            compensatable_order_amount,
-            payment_information_id: input.payment_information_id,
-            vat_number: input.vat_number,
-        };
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = build_order(ctx, input).await?;
+        verify_pending_orders_cap(&collection, &order, 0).await?;
         insert_order_in_mongodb(&collection, order).await
     }
 
+    /// Creates a batch of orders with `OrderStatus::Pending`.
+    ///
+    /// Each order is validated and created individually, the results are returned in the order of the inputs.
+    /// The batch size is limited by `$CREATE_ORDERS_MAX_BATCH_SIZE`.
+    async fn create_orders<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "CreateOrderInputs")] inputs: Vec<CreateOrderInput>,
+    ) -> Result<Vec<OrderCreationResult>> {
+        validate_batch_size(inputs.len())?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let mut maybe_orders: Vec<Result<Order>> = vec![];
+        for input in inputs {
+            let maybe_order = match build_order(ctx, input).await {
+                Ok(order) => {
+                    let pending_orders_in_batch =
+                        count_pending_orders_of_user_in_batch(&maybe_orders, order.user._id);
+                    verify_pending_orders_cap(&collection, &order, pending_orders_in_batch)
+                        .await
+                        .map(|_| order)
+                }
+                Err(error) => Err(error),
+            };
+            maybe_orders.push(maybe_order);
+        }
+        let orders: Vec<Order> = maybe_orders
+            .iter()
+            .filter_map(|maybe_order| maybe_order.as_ref().ok().cloned())
+            .collect();
+        let mut inserted_orders = insert_orders_in_mongodb(&collection, orders).await?;
+        let order_creation_results = maybe_orders
+            .into_iter()
+            .map(|maybe_order| match maybe_order {
+                Ok(order) => OrderCreationResult {
+                    order: inserted_orders.remove(&order._id),
+                    error: None,
+                },
+                Err(error) => OrderCreationResult {
+                    order: None,
+                    error: Some(error.message),
+                },
+            })
+            .collect();
+        Ok(order_creation_results)
+    }
+
     /// Places an existing order by changing its status to `OrderStatus::Placed`.
     ///
     /// Adds optional payment authorization input to order DTO when placing order.
+    /// Fails if the shipment or invoice address of the order was archived since order creation.
+    /// The order is marked as `event_published` only after the order created event was successfully published.
+    #[instrument(skip_all, fields(order_id = %input.id, user_id = field::Empty))]
     async fn place_order<'a>(
         &self,
         ctx: &Context<'a>,
         #[graphql(desc = "PlaceOrderInput")] input: PlaceOrderInput,
     ) -> Result<Order> {
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<Order> = db_client.collection::<Order>("orders");
-        let mut order = query_object(&collection, input.id).await?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = query_object(&collection, input.id).await?;
+        Span::current().record("user_id", field::display(order.user._id));
         authorize_user(&ctx, Some(order.user._id))?;
-        let payment_authorization = build_payment_authorization(&input);
-        set_status_placed(&collection, input.id).await?;
-        order = query_object(&collection, input.id).await?;
-        let order_dto = OrderDTO::try_from((order.clone(), payment_authorization))?;
-        send_order_created_event(order_dto).await?;
-        Ok(order)
+        let payment_authorization = build_payment_authorization(&input.payment_authorization);
+        place_and_publish_order(ctx, &collection, input.id, payment_authorization).await
+    }
+
+    /// Creates an order and immediately places it, skipping the `OrderStatus::Pending` window.
+    ///
+    /// Runs the same validation and availability checks as `create_order` and emits the order created event like `place_order`.
+    /// Restricted to trusted internal services, e.g. a subscription renewal job.
+    #[instrument(skip_all, fields(user_id = %input.order.user_id))]
+    async fn create_and_place_order<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "CreateAndPlaceOrderInput")] input: CreateAndPlaceOrderInput,
+    ) -> Result<Order> {
+        authorize_service(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let payment_authorization = build_payment_authorization(&input.payment_authorization);
+        let order = build_validated_order(ctx, input.order).await?;
+        let order = insert_order_in_mongodb(&collection, order).await?;
+        if order.order_status != OrderStatus::Pending {
+            return Ok(order);
+        }
+        place_and_publish_order(ctx, &collection, order._id, payment_authorization).await
+    }
+
+    /// Updates the quantity of an order item of an order with `OrderStatus::Pending`.
+    ///
+    /// Checks the availability of the new quantity, re-queries the applicable discounts of the order item and the shipment fees of the order for it,
+    /// and recalculates the compensatable amounts of the order item and the order.
+    async fn update_order_item_count<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UpdateOrderItemCountInput")] input: UpdateOrderItemCountInput,
+    ) -> Result<Order> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let mut order = query_object(&collection, input.order_id).await?;
+        authorize_user(ctx, Some(order.user._id))?;
+        if order.order_status != OrderStatus::Pending {
+            let message = format!(
+                "`{:?}` must be `OrderStatus::Pending` to be able to update order items.",
+                order.order_status
+            );
+            return Err(Error::new(message));
+        }
+        if input.count == 0 {
+            return Err(Error::new(
+                "Count of order item must be greater than zero, remove the order item instead.",
+            ));
+        }
+        let order_item_index = order
+            .internal_order_items
+            .iter()
+            .position(|order_item| order_item._id == input.order_item_id)
+            .ok_or_else(|| {
+                let message = format!(
+                    "OrderItem with UUID: `{}` is not contained in order of UUID: `{}`.",
+                    input.order_item_id, input.order_id
+                );
+                Error::new(message)
+            })?;
+        let product_variant_id = order.internal_order_items[order_item_index]
+            .product_variant
+            ._id;
+        let counts_by_product_variant_ids = HashMap::from([(product_variant_id, input.count)]);
+        verify_item_quantity_cap(&counts_by_product_variant_ids)?;
+        let correlation_id = ctx.data::<CorrelationId>()?;
+        check_product_variant_availability(
+            &vec![product_variant_id],
+            &counts_by_product_variant_ids,
+            correlation_id,
+        )
+        .await?;
+        order.internal_order_items[order_item_index].count = input.count;
+        if discounts_enabled() {
+            let internal_discounts = query_order_item_discounts(
+                order.user._id,
+                &order.internal_order_items[order_item_index],
+                &order.internal_order_items,
+                correlation_id,
+            )
+            .await?;
+            order.internal_order_items[order_item_index].internal_discounts = internal_discounts;
+        }
+        let rounding_strategy = ctx.data::<RoundingStrategy>()?;
+        order.internal_order_items[order_item_index]
+            .recalculate_compensatable_amount(*rounding_strategy);
+        let compensatable_order_amount =
+            calculate_compensatable_order_amount(&order.internal_order_items);
+        let shipping_total = match shipment_fees_enabled() {
+            true => {
+                query_order_items_shipment_fees(&order.internal_order_items, correlation_id).await?
+            }
+            false => 0,
+        };
+        set_order_item_count_in_mongodb(
+            &collection,
+            &input,
+            &order.internal_order_items[order_item_index],
+            compensatable_order_amount,
+            shipping_total,
+        )
+        .await?;
+        query_object(&collection, input.order_id).await
+    }
+
+    /// Updates the payment information of an order with `OrderStatus::Pending`.
+    ///
+    /// The payment information must belong to the user owning the order.
+    async fn update_order_payment_information<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UpdateOrderPaymentInformationInput")]
+        input: UpdateOrderPaymentInformationInput,
+    ) -> Result<Order> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = query_object(&collection, input.order_id).await?;
+        authorize_user(ctx, Some(order.user._id))?;
+        if order.order_status != OrderStatus::Pending {
+            let message = format!(
+                "`{:?}` must be `OrderStatus::Pending` to be able to update the payment information.",
+                order.order_status
+            );
+            return Err(Error::new(message));
+        }
+        validate_payment_information(db_client, input.payment_information_id, order.user._id)
+            .await?;
+        set_payment_information_in_mongodb(&collection, &input).await?;
+        query_object(&collection, input.order_id).await
+    }
+
+    /// Applies a coupon to an order item of an order with `OrderStatus::Pending`.
+    ///
+    /// Re-queries the applicable discounts of the order item including the coupon and recalculates the compensatable amounts of the order item and the order.
+    /// Fails if the coupon does not result in an additional discount.
+    async fn apply_coupon_to_order_item<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "ApplyCouponToOrderItemInput")] input: ApplyCouponToOrderItemInput,
+    ) -> Result<Order> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let mut order = query_object(&collection, input.order_id).await?;
+        authorize_user(ctx, Some(order.user._id))?;
+        if order.order_status != OrderStatus::Pending {
+            let message = format!(
+                "`{:?}` must be `OrderStatus::Pending` to be able to apply coupons.",
+                order.order_status
+            );
+            return Err(Error::new(message));
+        }
+        if !discounts_enabled() {
+            return Err(Error::new(
+                "Discounts are disabled, coupons can not be applied to order items.",
+            ));
+        }
+        let coupon_collection: Collection<Coupon> = db_client.collection::<Coupon>(COUPONS);
+        validate_object(&coupon_collection, input.coupon_id).await?;
+        let user_id = order.user._id;
+        if let Some(order_item) = order
+            .internal_order_items
+            .iter()
+            .find(|order_item| order_item.applied_coupon_ids.contains(&input.coupon_id))
+        {
+            let message = format!(
+                "Coupon with UUID: `{}` is already applied to order item of UUID: `{}`, a coupon can only be applied to one order item of an order.",
+                input.coupon_id, order_item._id
+            );
+            return Err(Error::new(message));
+        }
+        let order_item = order
+            .internal_order_items
+            .iter_mut()
+            .find(|order_item| order_item._id == input.order_item_id)
+            .ok_or_else(|| {
+                let message = format!(
+                    "OrderItem with UUID: `{}` is not contained in order of UUID: `{}`.",
+                    input.order_item_id, input.order_id
+                );
+                Error::new(message)
+            })?;
+        let correlation_id = ctx.data::<CorrelationId>()?;
+        let internal_discounts =
+            query_discounts_with_coupon(user_id, order_item, input.coupon_id, correlation_id)
+                .await?;
+        if internal_discounts.is_subset(&order_item.internal_discounts) {
+            let message = format!(
+                "Coupon with UUID: `{}` does not apply to order item of UUID: `{}`.",
+                input.coupon_id, input.order_item_id
+            );
+            return Err(Error::new(message));
+        }
+        order_item.internal_discounts = internal_discounts;
+        order_item.applied_coupon_ids.push(input.coupon_id);
+        order_item.applied_coupon_ids.sort();
+        let rounding_strategy = ctx.data::<RoundingStrategy>()?;
+        order_item.recalculate_compensatable_amount(*rounding_strategy);
+        let order_item = order_item.clone();
+        let compensatable_order_amount =
+            calculate_compensatable_order_amount(&order.internal_order_items);
+        set_order_item_discounts_in_mongodb(
+            &collection,
+            input.order_id,
+            &order_item,
+            compensatable_order_amount,
+        )
+        .await?;
+        query_object(&collection, input.order_id).await
+    }
+
+    /// Merges the order items of a secondary order into a primary order, both with `OrderStatus::Pending`.
+    ///
+    /// Order items of the same product variant are combined by summing up their counts, as each product variant may only occur once per order.
+    /// Checks the merged quantities against the maximum quantity per order item and their availability, recalculates the compensatable amounts and deletes the secondary order.
+    /// The shipment fees are queried for the merged order items, as fees charged once per shipment would otherwise be charged for both orders.
+    /// Both orders must belong to the same user.
+    async fn merge_pending_orders<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of order to merge order items into.")] primary_id: Uuid,
+        #[graphql(desc = "UUID of order to merge order items from, which is deleted afterwards.")]
+        secondary_id: Uuid,
+    ) -> Result<Order> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        if primary_id == secondary_id {
+            let message = format!(
+                "Order of UUID: `{}` can not be merged with itself.",
+                primary_id
+            );
+            return Err(Error::new(message));
+        }
+        let mut primary_order = query_object(&collection, primary_id).await?;
+        authorize_user(ctx, Some(primary_order.user._id))?;
+        let secondary_order = query_object(&collection, secondary_id).await?;
+        authorize_user(ctx, Some(secondary_order.user._id))?;
+        verify_orders_mergeable(&primary_order, &secondary_order)?;
+        let rounding_strategy = ctx.data::<RoundingStrategy>()?;
+        merge_order_items(
+            &mut primary_order.internal_order_items,
+            secondary_order.internal_order_items,
+            *rounding_strategy,
+        );
+        let counts_by_product_variant_ids: HashMap<Uuid, u64> = primary_order
+            .internal_order_items
+            .iter()
+            .map(|order_item| (order_item.product_variant._id, order_item.count))
+            .collect();
+        verify_item_quantity_cap(&counts_by_product_variant_ids)?;
+        let coupon_ids: Vec<Uuid> = primary_order
+            .internal_order_items
+            .iter()
+            .flat_map(|order_item| order_item.applied_coupon_ids.clone())
+            .collect();
+        verify_coupons_unique_across_order_items(&coupon_ids)?;
+        let correlation_id = ctx.data::<CorrelationId>()?;
+        check_order_items_availability(&primary_order.internal_order_items, correlation_id).await?;
+        let compensatable_order_amount =
+            calculate_compensatable_order_amount(&primary_order.internal_order_items);
+        let shipping_total = match shipment_fees_enabled() {
+            true => {
+                query_order_items_shipment_fees(&primary_order.internal_order_items, correlation_id)
+                    .await?
+            }
+            false => 0,
+        };
+        set_order_items_in_mongodb(
+            &collection,
+            primary_id,
+            &primary_order.internal_order_items,
+            compensatable_order_amount,
+            shipping_total,
+        )
+        .await?;
+        delete_order_in_mongodb(&collection, secondary_id).await?;
+        query_object(&collection, primary_id).await
+    }
+
+    /// Re-sends the `order/order/created` event of an order with `OrderStatus::Placed`.
+    ///
+    /// Recovers from a failed event publication after the order placement was already saved in MongoDB.
+    /// The payment authorization is not persisted and therefore not contained in the re-sent event.
+    /// Only accessible to admins.
+    async fn resend_order_created_event<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of order to re-send the order created event for.")] order_id: Uuid,
+    ) -> Result<Order> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = query_object(&collection, order_id).await?;
+        if order.order_status != OrderStatus::Placed {
+            let message = format!(
+                "`{:?}` must be `OrderStatus::Placed` to be able to re-send the order created event of order of UUID: `{}`.",
+                order.order_status, order_id
+            );
+            return Err(Error::new(message));
+        }
+        let order_dto = OrderDTO::try_from((order.clone(), None))?;
+        let correlation_id = ctx.data::<CorrelationId>()?;
+        let event_config = ctx.data::<EventConfig>()?;
+        send_order_created_event(event_config, order_dto, correlation_id).await?;
+        set_event_published_in_mongodb(&collection, order_id).await?;
+        query_object(&collection, order_id).await
+    }
+
+    /// Refunds order items of an order, which creates an order compensation.
+    ///
+    /// Only order items of an order with `OrderStatus::Placed` which were not already compensated can be refunded.
+    /// Only accessible to admins.
+    async fn refund_order_items<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "RefundOrderItemsInput")] input: RefundOrderItemsInput,
+    ) -> Result<OrderCompensation> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let order_collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order_compensation_collection: Collection<OrderCompensation> =
+            db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS);
+        let event_config = ctx.data::<EventConfig>()?;
+        refund_order_items(
+            &order_collection,
+            &order_compensation_collection,
+            input.order_id,
+            input.order_item_ids.into_iter().collect(),
+            input.reason,
+            event_config,
+        )
+        .await
+    }
+}
+
+/// Builds an order with `OrderStatus::Pending` from a create order input.
+///
+/// Authorizes the user and validates the input before building the order, which is not persisted.
+///
+/// * `ctx` - GraphQL context containing the `Authorized-User` header and the database client.
+/// * `input` - Create order input to build the order from.
+pub async fn build_order<'a>(ctx: &Context<'a>, input: CreateOrderInput) -> Result<Order> {
+    authorize_user(ctx, Some(input.user_id))?;
+    build_validated_order(ctx, input).await
+}
+
+/// Builds an order with `OrderStatus::Pending` from a create order input without authorizing the user.
+///
+/// Validates the input before building the order, the caller is responsible for authorization.
+///
+/// * `ctx` - GraphQL context containing the `Authorized-User` header and the database client.
+/// * `input` - Create order input to build the order from.
+async fn build_validated_order<'a>(ctx: &Context<'a>, input: CreateOrderInput) -> Result<Order> {
+    let db_client = ctx.data::<Database>()?;
+    validate_order_input(db_client, &input).await?;
+    let customer_note = validate_customer_note(input.customer_note.clone())?;
+    let gift_recipient_address =
+        validate_gift_recipient_address(input.is_gift, input.gift_recipient_address.clone())?;
+    let store_config = ctx.data::<StoreConfig>()?;
+    let vat_number = validate_vat_number(input.vat_number.clone(), &store_config.country)?;
+    let current_timestamp = DateTime::now();
+    let currency_conversion = CurrencyConversion::determine(store_config, current_timestamp)?;
+    let (internal_order_items, shipping_total) =
+        create_internal_order_items(ctx, &input, current_timestamp).await?;
+    let correlation_id = ctx.data::<CorrelationId>()?;
+    let (order_status, rejection_reason) =
+        match check_order_items_availability(&internal_order_items, correlation_id).await {
+            Ok(()) => (OrderStatus::Pending, None),
+            Err(error)
+                if is_product_variants_unavailable(&error)
+                    && inventory_rejected_orders_persisted() =>
+            {
+                (
+                    OrderStatus::Rejected,
+                    Some(RejectionReason::InventoryReservationFailed),
+                )
+            }
+            Err(error) => return Err(error),
+        };
+    let shipment_address = UserAddress::from(input.shipment_address_id);
+    let invoice_address = UserAddress::from(input.invoice_address_id);
+    let compensatable_order_amount = calculate_compensatable_order_amount(&internal_order_items);
+    let order = Order {
+        _id: Uuid::new(),
+        user: User::from(input.user_id),
+        created_at: current_timestamp,
+        order_status,
+        placed_at: None,
+        last_updated_at: Some(current_timestamp),
+        rejection_reason,
+        internal_order_items,
+        shipment_address,
+        invoice_address,
+        is_gift: input.is_gift,
+        gift_recipient_address,
+        compensatable_order_amount,
+        shipping_total,
+        payment_information_id: input.payment_information_id,
+        vat_number,
+        customer_note,
+        status_history: vec![StatusHistoryEntry::new(order_status, current_timestamp)],
+        event_published: false,
+        payment_authorization_provided: false,
+        currency_conversion: Some(currency_conversion),
+    };
+    Ok(order)
+}
+
+/// Defines if orders whose order items are unavailable are persisted as `OrderStatus::Rejected` with `RejectionReason::InventoryReservationFailed`.
+///
+/// Otherwise order creation fails with the availability error and no order is persisted.
+/// Other errors of the availability check, e.g. transport errors, always fail order creation.
+/// Read from `$PERSIST_INVENTORY_REJECTED_ORDERS`, defaults to `false`.
+fn inventory_rejected_orders_persisted() -> bool {
+    env::var("PERSIST_INVENTORY_REJECTED_ORDERS")
+        .ok()
+        .and_then(|persisted| persisted.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Normalizes an optional VAT number and checks its format.
+///
+/// Whitespace is removed and letters are uppercased. VAT numbers without a country prefix are prefixed with the country of the store.
+/// The country prefix must consist of 2 ASCII letters and the part following it of 2 to 12 ASCII alphanumeric characters.
+///
+/// * `vat_number` - Optional VAT number of create order input.
+/// * `store_country` - ISO 3166-1 alpha-2 code of the country of the store.
+fn validate_vat_number(vat_number: Option<String>, store_country: &str) -> Result<Option<String>> {
+    let Some(vat_number) = vat_number else {
+        return Ok(None);
+    };
+    let normalized_vat_number: String = vat_number
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase();
+    let has_country_prefix = normalized_vat_number
+        .chars()
+        .take(2)
+        .filter(|c| c.is_ascii_alphabetic())
+        .count()
+        == 2;
+    let prefixed_vat_number = match has_country_prefix {
+        true => normalized_vat_number,
+        false => format!("{}{}", store_country.to_uppercase(), normalized_vat_number),
+    };
+    let is_valid_format = match prefixed_vat_number.split_at_checked(2) {
+        Some((country_prefix, number)) => {
+            country_prefix.chars().all(|c| c.is_ascii_alphabetic())
+                && (2..=12).contains(&number.len())
+                && number.chars().all(|c| c.is_ascii_alphanumeric())
+        }
+        None => false,
+    };
+    match is_valid_format {
+        true => Ok(Some(prefixed_vat_number)),
+        false => {
+            let message = format!("VAT number: `{}` has an invalid format.", vat_number);
+            Err(Error::new(message))
+        }
     }
 }
 
-/// Builds payment authorization from place order input.
+/// Validates the optional gift recipient address of a create order input.
+///
+/// Only gift orders can have a gift recipient address.
 ///
-/// `input` - The place order input to build the payment authorization from.
-fn build_payment_authorization(input: &PlaceOrderInput) -> Option<PaymentAuthorization> {
-    input
-        .payment_authorization
+/// * `is_gift` - Whether the order is a gift.
+/// * `gift_recipient_address` - Optional gift recipient address of create order input.
+fn validate_gift_recipient_address(
+    is_gift: bool,
+    gift_recipient_address: Option<GiftRecipientAddressInput>,
+) -> Result<Option<GiftRecipientAddress>> {
+    match (is_gift, gift_recipient_address) {
+        (_, None) => Ok(None),
+        (true, Some(gift_recipient_address)) => Ok(Some(GiftRecipientAddress::try_from(
+            gift_recipient_address,
+        )?)),
+        (false, Some(_)) => Err(Error::new(
+            "A gift recipient address can only be set for gift orders.",
+        )),
+    }
+}
+
+/// Trims an optional customer note and checks that it does not exceed `MAX_CUSTOMER_NOTE_LENGTH` characters.
+///
+/// Returns `None` if the trimmed customer note is empty.
+///
+/// * `customer_note` - Optional customer note of create order input.
+fn validate_customer_note(customer_note: Option<String>) -> Result<Option<String>> {
+    let trimmed_customer_note = customer_note
+        .map(|customer_note| customer_note.trim().to_string())
+        .filter(|customer_note| !customer_note.is_empty());
+    match &trimmed_customer_note {
+        Some(customer_note) if customer_note.chars().count() > MAX_CUSTOMER_NOTE_LENGTH => {
+            let message = format!(
+                "Customer note of `{}` characters exceeds the maximum length of `{}` characters.",
+                customer_note.chars().count(),
+                MAX_CUSTOMER_NOTE_LENGTH
+            );
+            Err(Error::new(message))
+        }
+        _ => Ok(trimmed_customer_note),
+    }
+}
+
+/// Checks that a batch of create order inputs does not exceed the maximum batch size.
+///
+/// The maximum batch size is read from `$CREATE_ORDERS_MAX_BATCH_SIZE` and defaults to `DEFAULT_CREATE_ORDERS_MAX_BATCH_SIZE`.
+///
+/// * `batch_size` - Amount of create order inputs in the batch.
+fn validate_batch_size(batch_size: usize) -> Result<()> {
+    let max_batch_size = env::var("CREATE_ORDERS_MAX_BATCH_SIZE")
+        .ok()
+        .and_then(|max_batch_size| max_batch_size.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_CREATE_ORDERS_MAX_BATCH_SIZE);
+    match batch_size <= max_batch_size {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "Batch of `{}` orders exceeds the maximum batch size of `{}`.",
+                batch_size, max_batch_size
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Checks that creating a pending order does not exceed the maximum number of pending orders of its user.
+///
+/// The maximum is read from `$MAX_PENDING_ORDERS_PER_USER`, the number of pending orders is unlimited if it is not set.
+/// Orders which are not `OrderStatus::Pending`, e.g. persisted rejected orders, are not limited.
+///
+/// * `collection` - MongoDB collection to count pending orders of user in.
+/// * `order` - Order to create.
+/// * `pending_orders_in_batch` - Amount of pending orders of the user, which are created in the same batch before the order.
+async fn verify_pending_orders_cap(
+    collection: &Collection<Order>,
+    order: &Order,
+    pending_orders_in_batch: u64,
+) -> Result<()> {
+    let Some(max_pending_orders) = max_pending_orders_per_user() else {
+        return Ok(());
+    };
+    if order.order_status != OrderStatus::Pending {
+        return Ok(());
+    }
+    let filter = doc! {"user._id": order.user._id, "order_status": OrderStatus::Pending};
+    let pending_orders = collection
+        .count_documents(filter, None)
+        .await
+        .map_err(|_| Error::new("Pending orders of user could not be counted."))?;
+    match pending_orders + pending_orders_in_batch < max_pending_orders {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "User of UUID: `{}` already has the maximum of `{}` pending orders.",
+                order.user._id, max_pending_orders
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Counts the pending orders of a user in a batch of built orders.
+///
+/// * `maybe_orders` - Built orders or errors of a batch.
+/// * `user_id` - UUID of user to count pending orders of.
+fn count_pending_orders_of_user_in_batch(maybe_orders: &[Result<Order>], user_id: Uuid) -> u64 {
+    maybe_orders
+        .iter()
+        .filter_map(|maybe_order| maybe_order.as_ref().ok())
+        .filter(|order| order.user._id == user_id && order.order_status == OrderStatus::Pending)
+        .count() as u64
+}
+
+/// Maximum number of simultaneous pending orders per user, read from `$MAX_PENDING_ORDERS_PER_USER`.
+///
+/// Returns `None` if the number of pending orders is unlimited.
+fn max_pending_orders_per_user() -> Option<u64> {
+    env::var("MAX_PENDING_ORDERS_PER_USER")
+        .ok()
+        .and_then(|max_pending_orders| max_pending_orders.parse::<u64>().ok())
+}
+
+/// Maximum quantity of a single order item, read from `$MAX_ITEM_QUANTITY`.
+///
+/// Returns `None` if the quantity of order items is unlimited.
+fn max_item_quantity() -> Option<u64> {
+    env::var("MAX_ITEM_QUANTITY")
+        .ok()
+        .and_then(|max_item_quantity| max_item_quantity.parse::<u64>().ok())
+}
+
+/// Checks that no order item exceeds the maximum quantity per order item.
+///
+/// The maximum is read from `$MAX_ITEM_QUANTITY`, the quantity of order items is unlimited if it is not set.
+///
+/// * `counts_by_product_variant_ids` - Requested counts of the order items by product variant UUIDs.
+fn verify_item_quantity_cap(counts_by_product_variant_ids: &HashMap<Uuid, u64>) -> Result<()> {
+    verify_item_quantity_cap_of(counts_by_product_variant_ids, max_item_quantity())
+}
+
+/// Checks that no order item exceeds a maximum quantity per order item.
+///
+/// Reports the order item of the smallest product variant UUID if multiple order items exceed the maximum.
+///
+/// * `counts_by_product_variant_ids` - Requested counts of the order items by product variant UUIDs.
+/// * `max_item_quantity` - Maximum quantity per order item, `None` if the quantity is unlimited.
+fn verify_item_quantity_cap_of(
+    counts_by_product_variant_ids: &HashMap<Uuid, u64>,
+    max_item_quantity: Option<u64>,
+) -> Result<()> {
+    let Some(max_item_quantity) = max_item_quantity else {
+        return Ok(());
+    };
+    let mut exceeding_product_variants: Vec<(&Uuid, &u64)> = counts_by_product_variant_ids
+        .iter()
+        .filter(|(_, count)| **count > max_item_quantity)
+        .collect();
+    exceeding_product_variants.sort();
+    match exceeding_product_variants.first() {
+        Some((product_variant_id, count)) => {
+            let message = format!(
+                "Quantity: `{}` of order item of product variant with UUID: `{}` exceeds the maximum quantity per order item of `{}`.",
+                count, product_variant_id, max_item_quantity
+            );
+            Err(Error::new(message))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Places an order with `OrderStatus::Pending` and publishes its order created event.
+///
+/// The order is marked as `event_published` only after the order created event was successfully published.
+/// Unpublished order created events are republished by `republish_unpublished_order_created_events`.
+///
+/// * `ctx` - GraphQL context containing the correlation id.
+/// * `collection` - MongoDB collection containing the order.
+/// * `id` - UUID of order to place.
+/// * `payment_authorization` - Optional payment authorization to add to the order DTO.
+async fn place_and_publish_order<'a>(
+    ctx: &Context<'a>,
+    collection: &Collection<Order>,
+    id: Uuid,
+    payment_authorization: Option<PaymentAuthorization>,
+) -> Result<Order> {
+    let order = query_object(collection, id).await?;
+    let db_client = ctx.data::<Database>()?;
+    validate_addresses_at_placement(db_client, &order).await?;
+    set_status_placed(collection, id, payment_authorization.is_some()).await?;
+    let order = query_object(collection, id).await?;
+    let order_dto = OrderDTO::try_from((order, payment_authorization))?;
+    let correlation_id = ctx.data::<CorrelationId>()?;
+    let event_config = ctx.data::<EventConfig>()?;
+    send_order_created_event(event_config, order_dto, correlation_id).await?;
+    set_event_published_in_mongodb(collection, id).await?;
+    query_object(collection, id).await
+}
+
+/// Builds payment authorization from optional payment authorization input.
+///
+/// `payment_authorization_input` - The optional payment authorization input to build the payment authorization from.
+fn build_payment_authorization(
+    payment_authorization_input: &Option<PaymentAuthorizationInput>,
+) -> Option<PaymentAuthorization> {
+    payment_authorization_input
         .clone()
         .and_then(|definitely_payment_authorization| {
             Option::<PaymentAuthorization>::from(definitely_payment_authorization)
@@ -112,11 +829,17 @@ fn build_payment_authorization(input: &PlaceOrderInput) -> Option<PaymentAuthori
 
 /// Inserts order in MongoDB and returns the order itself.
 ///
+/// Counts the order as rejected if it is inserted with `OrderStatus::Rejected`.
+///
 /// * `collection` - MongoDB collection to insert order in.
 /// * `order` - Order to insert.
 async fn insert_order_in_mongodb(collection: &Collection<Order>, order: Order) -> Result<Order> {
+    let rejection_reason = order.rejection_reason;
     match collection.insert_one(order, None).await {
         Ok(result) => {
+            if let Some(rejection_reason) = rejection_reason {
+                increment_rejected_orders(rejection_reason);
+            }
             let id = uuid_from_bson(result.inserted_id)?;
             query_object(&collection, id).await
         }
@@ -124,122 +847,564 @@ async fn insert_order_in_mongodb(collection: &Collection<Order>, order: Order) -
     }
 }
 
+/// Inserts orders in MongoDB and returns the orders themselves by UUID.
+///
+/// Counts the orders as rejected which are inserted with `OrderStatus::Rejected`.
+///
+/// * `collection` - MongoDB collection to insert orders in.
+/// * `orders` - Orders to insert.
+async fn insert_orders_in_mongodb(
+    collection: &Collection<Order>,
+    orders: Vec<Order>,
+) -> Result<HashMap<Uuid, Order>> {
+    if orders.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let rejection_reasons: Vec<RejectionReason> = orders
+        .iter()
+        .filter_map(|order| order.rejection_reason)
+        .collect();
+    match collection.insert_many(orders, None).await {
+        Ok(result) => {
+            rejection_reasons
+                .into_iter()
+                .for_each(increment_rejected_orders);
+            let ids = result
+                .inserted_ids
+                .into_values()
+                .map(uuid_from_bson)
+                .collect::<Result<Vec<Uuid>>>()?;
+            query_objects(collection, &ids).await
+        }
+        Err(_) => Err(Error::new("Adding orders failed in MongoDB.")),
+    }
+}
+
 /// Calculates the total compensatable amount of all order items in the input by summing up their `compensatable_amount` attributes.
 ///
-/// `order_items` - Order items to calculate compensatable amount for.
-fn calculate_compensatable_order_amount(order_items: &Vec<OrderItem>) -> u64 {
-    order_items
-        .iter()
-        .map(|order_item| order_item.compensatable_amount)
-        .sum()
+/// `order_items` - Order items to calculate compensatable amount for.
+fn calculate_compensatable_order_amount(order_items: &Vec<OrderItem>) -> u64 {
+    order_items
+        .iter()
+        .map(|order_item| order_item.compensatable_amount)
+        .sum()
+}
+
+/// Verifies that two orders can be merged, otherwise returns an error.
+///
+/// Both orders must be `OrderStatus::Pending` and belong to the same user.
+///
+/// * `primary_order` - Order to merge order items into.
+/// * `secondary_order` - Order to merge order items from.
+fn verify_orders_mergeable(primary_order: &Order, secondary_order: &Order) -> Result<()> {
+    for order in [primary_order, secondary_order] {
+        if order.order_status != OrderStatus::Pending {
+            let message = format!(
+                "`{:?}` of order of UUID: `{}` must be `OrderStatus::Pending` to be able to merge orders.",
+                order.order_status, order._id
+            );
+            return Err(Error::new(message));
+        }
+    }
+    if primary_order.user._id != secondary_order.user._id {
+        let message = format!(
+            "Orders of UUIDs: `{}` and `{}` belong to different users and can not be merged.",
+            primary_order._id, secondary_order._id
+        );
+        return Err(Error::new(message));
+    }
+    Ok(())
+}
+
+/// Merges order items into the order items of another order.
+///
+/// Order items of a product variant already contained in the order items have their count added to the existing order item, whose compensatable amount is recalculated.
+///
+/// * `order_items` - Order items to merge into.
+/// * `order_items_to_merge` - Order items to merge.
+/// * `rounding_strategy` - Strategy to round the recalculated compensatable amounts to minor units.
+fn merge_order_items(
+    order_items: &mut Vec<OrderItem>,
+    order_items_to_merge: Vec<OrderItem>,
+    rounding_strategy: RoundingStrategy,
+) {
+    for order_item_to_merge in order_items_to_merge {
+        match order_items.iter_mut().find(|order_item| {
+            order_item.product_variant._id == order_item_to_merge.product_variant._id
+        }) {
+            Some(order_item) => {
+                order_item.count += order_item_to_merge.count;
+                order_item.recalculate_compensatable_amount(rounding_strategy);
+            }
+            None => order_items.push(order_item_to_merge),
+        }
+    }
+}
+
+/// Extracts UUID from Bson.
+///
+/// Creating a order returns a UUID in a Bson document. This function helps to extract the UUID.
+///
+/// * `bson` - BSON document to extract UUID from.
+fn uuid_from_bson(bson: Bson) -> Result<Uuid> {
+    match bson {
+        Bson::Binary(id) => Ok(id.to_uuid()?),
+        _ => {
+            let message = format!(
+                "Returned id: `{}` needs to be a Binary in order to be parsed as a Uuid",
+                bson
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Sets the status of an order to `OrderStatus::Placed`.
+/// Checks if pending order is still valid before setting `OrderStatus::Placed`.
+/// Rejects order if timestamp of placement exceeds `PENDING_TIMEOUT` in relation to the order creation timestamp.
+///
+/// * `collection` - MongoDB collection to update.
+/// * `id` - UUID of order to set the order status to placed.
+/// * `payment_authorization_provided` - Whether a payment authorization was provided on placement.
+async fn set_status_placed(
+    collection: &Collection<Order>,
+    id: Uuid,
+    payment_authorization_provided: bool,
+) -> Result<()> {
+    let current_timestamp_system_time = SystemTime::now();
+    let order = query_object(&collection, id).await?;
+    if order.is_within_pending_timeout(current_timestamp_system_time) {
+        match order.order_status {
+            OrderStatus::Pending => {
+                let current_timestamp = DateTime::from(current_timestamp_system_time);
+                set_status_placed_in_mongodb(
+                    collection,
+                    id,
+                    current_timestamp,
+                    payment_authorization_provided,
+                )
+                .await
+            }
+            _ => {
+                let message = format!("`{:?}` must be `OrderStatus::Pending` to be able to be placed. Order was already placed or rejected.", order.order_status);
+                Err(Error::new(message))
+            }
+        }
+    } else {
+        reject_pending_timeout_exceeded(collection, id).await
+    }
+}
+
+/// Builds the MongoDB filter of an order while it is `OrderStatus::Pending`.
+///
+/// Updates with this filter do not match orders which were concurrently placed or rejected.
+///
+/// * `id` - UUID of the order.
+fn build_pending_order_filter(id: Uuid) -> Document {
+    doc! {"_id": id, "order_status": OrderStatus::Pending }
+}
+
+/// Updates order to `OrderStatus::Placed` in MongoDB.
+///
+/// The update only matches the order while it is `OrderStatus::Pending`, which makes the placement atomic.
+/// If a concurrent placement already transitioned the order, no order is matched and an error is returned.
+///
+/// * `collection` - MongoDB collection to set the order status as placed in.
+/// * `id` - UUID of order to set the order status to placed.
+/// * `current_timestamp` - Timestamp of order placement.
+/// * `payment_authorization_provided` - Whether a payment authorization was provided on placement.
+async fn set_status_placed_in_mongodb(
+    collection: &Collection<Order>,
+    id: Uuid,
+    current_timestamp: DateTime,
+    payment_authorization_provided: bool,
+) -> Result<()> {
+    let result = collection
+        .update_one(
+            build_pending_order_filter(id),
+            doc! {
+                "$set": {"order_status": OrderStatus::Placed, "placed_at": current_timestamp, "last_updated_at": current_timestamp, "payment_authorization_provided": payment_authorization_provided},
+                "$push": {"status_history": StatusHistoryEntry::new(OrderStatus::Placed, current_timestamp)},
+            },
+            None,
+        )
+        .await;
+    match result {
+        Ok(update_result) if update_result.matched_count == 0 => {
+            let message = format!(
+                "Order of id: `{}` must be `OrderStatus::Pending` to be able to be placed. Order was already placed or rejected concurrently.",
+                id
+            );
+            Err(Error::new(message))
+        }
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let message = format!("Placing order of id: `{}` failed in MongoDB.", id);
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Queries the applicable discounts of an order item including an additional coupon.
+///
+/// * `user_id` - UUID of user owning the order of the order item.
+/// * `order_item` - Order item to query applicable discounts for.
+/// * `coupon_id` - UUID of additional coupon to query applicable discounts with.
+/// * `correlation_id` - Correlation id to forward to the discount service.
+async fn query_discounts_with_coupon(
+    user_id: Uuid,
+    order_item: &OrderItem,
+    coupon_id: Uuid,
+    correlation_id: &CorrelationId,
+) -> Result<BTreeSet<Discount>> {
+    let product_variant_id = order_item.product_variant._id;
+    let mut coupon_ids: HashSet<Uuid> = order_item.applied_coupon_ids.iter().cloned().collect();
+    coupon_ids.insert(coupon_id);
+    let order_item_input = OrderItemInput {
+        shopping_cart_item_id: order_item.shopping_cart_item._id,
+        shipment_method_id: order_item.shipment_method._id,
+        coupon_ids,
+    };
+    let mut discounts_by_product_variant_ids = query_discounts_by_product_variant_ids(
+        user_id,
+        &HashMap::from([(product_variant_id, order_item_input)]),
+        &vec![product_variant_id],
+        &HashMap::from([(product_variant_id, order_item.product_variant_version)]),
+        &HashMap::from([(product_variant_id, order_item.count)]),
+        correlation_id,
+    )
+    .await?;
+    Ok(discounts_by_product_variant_ids
+        .remove(&product_variant_id)
+        .unwrap_or_default())
+}
+
+/// Updates the discounts, applied coupons and compensatable amount of an order item and the compensatable amount of its order in MongoDB.
+///
+/// * `collection` - MongoDB collection to update the order item in.
+/// * `order_id` - UUID of order containing the order item.
+/// * `order_item` - Order item with updated discounts, applied coupons and compensatable amount.
+/// * `compensatable_order_amount` - Recalculated compensatable amount of the order.
+async fn set_order_item_discounts_in_mongodb(
+    collection: &Collection<Order>,
+    order_id: Uuid,
+    order_item: &OrderItem,
+    compensatable_order_amount: u64,
+) -> Result<()> {
+    let internal_discounts = bson::to_bson(&order_item.internal_discounts)?;
+    let compensatable_amount = i64::try_from(order_item.compensatable_amount)?;
+    let compensatable_order_amount = i64::try_from(compensatable_order_amount)?;
+    let result = collection
+        .update_one(
+            doc! {"_id": order_id, "internal_order_items._id": order_item._id },
+            doc! {"$set": {
+                "internal_order_items.$.internal_discounts": internal_discounts,
+                "internal_order_items.$.applied_coupon_ids": &order_item.applied_coupon_ids,
+                "internal_order_items.$.compensatable_amount": compensatable_amount,
+                "internal_order_items.$.compensatable_amount_includes_count": true,
+                "compensatable_order_amount": compensatable_order_amount,
+                "last_updated_at": DateTime::now(),
+            }},
+            None,
+        )
+        .await;
+    if result.is_err() {
+        let message = format!(
+            "Updating discounts of order item of id: `{}` failed in MongoDB.",
+            order_item._id
+        );
+        return Err(Error::new(message));
+    }
+    Ok(())
+}
+
+/// Builds the MongoDB filter of an order item while its order is `OrderStatus::Pending`.
+///
+/// Updates with this filter do not match order items of orders which were concurrently placed or rejected.
+///
+/// * `order_id` - UUID of the order containing the order item.
+/// * `order_item_id` - UUID of the order item.
+fn build_pending_order_item_filter(order_id: Uuid, order_item_id: Uuid) -> Document {
+    let mut filter = build_pending_order_filter(order_id);
+    filter.insert("internal_order_items._id", order_item_id);
+    filter
 }
 
-/// Extracts UUID from Bson.
+/// Updates the quantity, discounts and compensatable amount of an order item and the compensatable amount and shipping total of its order in MongoDB.
 ///
-/// Creating a order returns a UUID in a Bson document. This function helps to extract the UUID.
+/// The update only matches the order while it is `OrderStatus::Pending`, so that a concurrently placed order keeps its order items.
 ///
-/// * `bson` - BSON document to extract UUID from.
-fn uuid_from_bson(bson: Bson) -> Result<Uuid> {
-    match bson {
-        Bson::Binary(id) => Ok(id.to_uuid()?),
-        _ => {
+/// * `collection` - MongoDB collection to update the order item in.
+/// * `input` - Update order item count input describing the order item and its new quantity.
+/// * `order_item` - Order item with the new quantity and its recalculated discounts and compensatable amount.
+/// * `compensatable_order_amount` - Recalculated compensatable amount of the order.
+/// * `shipping_total` - Recalculated total shipment fees of the order.
+async fn set_order_item_count_in_mongodb(
+    collection: &Collection<Order>,
+    input: &UpdateOrderItemCountInput,
+    order_item: &OrderItem,
+    compensatable_order_amount: u64,
+    shipping_total: u64,
+) -> Result<()> {
+    let count = i64::try_from(order_item.count)?;
+    let compensatable_amount = i64::try_from(order_item.compensatable_amount)?;
+    let internal_discounts = bson::to_bson(&order_item.internal_discounts)?;
+    let compensatable_order_amount = i64::try_from(compensatable_order_amount)?;
+    let shipping_total = i64::try_from(shipping_total)?;
+    let result = collection
+        .update_one(
+            build_pending_order_item_filter(input.order_id, input.order_item_id),
+            doc! {"$set": {
+                "internal_order_items.$.count": count,
+                "internal_order_items.$.compensatable_amount": compensatable_amount,
+                "internal_order_items.$.compensatable_amount_includes_count": true,
+                "internal_order_items.$.internal_discounts": internal_discounts,
+                "compensatable_order_amount": compensatable_order_amount,
+                "shipping_total": shipping_total,
+                "last_updated_at": DateTime::now(),
+            }},
+            None,
+        )
+        .await;
+    match result {
+        Ok(update_result) if update_result.matched_count == 0 => {
             let message = format!(
-                "Returned id: `{}` needs to be a Binary in order to be parsed as a Uuid",
-                bson
+                "Order of id: `{}` must be `OrderStatus::Pending` to be able to update order items. Order was placed or rejected concurrently.",
+                input.order_id
+            );
+            Err(Error::new(message))
+        }
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let message = format!(
+                "Updating count of order item of id: `{}` failed in MongoDB.",
+                input.order_item_id
             );
             Err(Error::new(message))
         }
     }
 }
 
-/// Sets the status of an order to `OrderStatus::Placed`.
-/// Checks if pending order is still valid before setting `OrderStatus::Placed`.
-/// Rejects order if timestamp of placement exceeds `PENDING_TIMEOUT` in relation to the order creation timestamp.
+/// Updates the payment information of an order in MongoDB.
 ///
-/// * `collection` - MongoDB collection to update.
-/// * `id` - UUID of order to set the order status to placed.
-async fn set_status_placed(collection: &Collection<Order>, id: Uuid) -> Result<()> {
-    let current_timestamp_system_time = SystemTime::now();
-    let order = query_object(&collection, id).await?;
-    let order_created_at_system_time = order.created_at.to_system_time();
-    if order_created_at_system_time + PENDING_TIMEOUT >= current_timestamp_system_time {
-        match order.order_status {
-            OrderStatus::Pending => {
-                let current_timestamp = DateTime::from(current_timestamp_system_time);
-                set_status_placed_in_mongodb(&collection, id, current_timestamp).await
-            }
-            _ => {
-                let message = format!("`{:?}` must be `OrderStatus::Pending` to be able to be placed. Order was already placed or rejected.", order.order_status);
-                Err(Error::new(message))
-            }
+/// The update only matches the order while it is `OrderStatus::Pending`, so that a concurrently placed order keeps its payment information.
+///
+/// * `collection` - MongoDB collection to update the order in.
+/// * `input` - Input containing the UUIDs of the order and the new payment information.
+async fn set_payment_information_in_mongodb(
+    collection: &Collection<Order>,
+    input: &UpdateOrderPaymentInformationInput,
+) -> Result<()> {
+    let result = collection
+        .update_one(
+            doc! {"_id": input.order_id, "order_status": OrderStatus::Pending },
+            doc! {"$set": {
+                "payment_information_id": input.payment_information_id,
+                "last_updated_at": DateTime::now(),
+            }},
+            None,
+        )
+        .await;
+    match result {
+        Ok(update_result) if update_result.matched_count == 0 => {
+            let message = format!(
+                "Order of id: `{}` must be `OrderStatus::Pending` to be able to update the payment information. Order was placed or rejected concurrently.",
+                input.order_id
+            );
+            Err(Error::new(message))
+        }
+        Ok(_) => Ok(()),
+        Err(_) => {
+            let message = format!(
+                "Updating payment information of order of id: `{}` failed in MongoDB.",
+                input.order_id
+            );
+            Err(Error::new(message))
         }
-    } else {
-        set_status_rejected_in_mongodb(&collection, id).await
     }
 }
 
-/// Updates order to `OrderStatus::Placed` in MongoDB.
+/// Replaces the order items, the compensatable amount and the shipping total of an order in MongoDB.
 ///
-/// * `collection` - MongoDB collection to set the order status as placed in.
-/// * `id` - UUID of order to set the order status to placed.
-/// * `current_timestamp` - Timestamp of order placement.
-async fn set_status_placed_in_mongodb(
+/// * `collection` - MongoDB collection to update the order in.
+/// * `id` - UUID of order to replace order items of.
+/// * `order_items` - New order items of the order.
+/// * `compensatable_order_amount` - Recalculated compensatable amount of the order.
+/// * `shipping_total` - Recalculated total shipment fees of the order.
+async fn set_order_items_in_mongodb(
     collection: &Collection<Order>,
     id: Uuid,
-    current_timestamp: DateTime,
+    order_items: &Vec<OrderItem>,
+    compensatable_order_amount: u64,
+    shipping_total: u64,
 ) -> Result<()> {
+    let internal_order_items = bson::to_bson(order_items)?;
+    let compensatable_order_amount = i64::try_from(compensatable_order_amount)?;
+    let shipping_total = i64::try_from(shipping_total)?;
+    let result = collection
+        .update_one(
+            doc! {"_id": id },
+            doc! {"$set": {
+                "internal_order_items": internal_order_items,
+                "compensatable_order_amount": compensatable_order_amount,
+                "shipping_total": shipping_total,
+                "last_updated_at": DateTime::now(),
+            }},
+            None,
+        )
+        .await;
+    if result.is_err() {
+        let message = format!(
+            "Updating order items of order of id: `{}` failed in MongoDB.",
+            id
+        );
+        return Err(Error::new(message));
+    }
+    Ok(())
+}
+
+/// Deletes an order in MongoDB.
+///
+/// * `collection` - MongoDB collection to delete the order in.
+/// * `id` - UUID of order to delete.
+async fn delete_order_in_mongodb(collection: &Collection<Order>, id: Uuid) -> Result<()> {
+    if collection
+        .delete_one(doc! {"_id": id }, None)
+        .await
+        .is_err()
+    {
+        let message = format!("Deleting order of id: `{}` failed in MongoDB.", id);
+        return Err(Error::new(message));
+    }
+    Ok(())
+}
+
+/// Marks the order created event of an order as published in MongoDB.
+///
+/// * `collection` - MongoDB collection to mark the order in.
+/// * `id` - UUID of order to mark.
+async fn set_event_published_in_mongodb(collection: &Collection<Order>, id: Uuid) -> Result<()> {
     let result = collection
         .update_one(
             doc! {"_id": id },
-            doc! {"$set": {"order_status": OrderStatus::Placed, "placed_at": current_timestamp}},
+            doc! {"$set": {"event_published": true}},
             None,
         )
         .await;
-    if let Err(_) = result {
-        let message = format!("Placing order of id: `{}` failed in MongoDB.", id);
+    if result.is_err() {
+        let message = format!(
+            "Marking order created event of order of id: `{}` as published failed in MongoDB.",
+            id
+        );
         return Err(Error::new(message));
     }
     Ok(())
 }
 
-/// Updates order to `OrderStatus::Rejected` in MongoDB.
+/// Rejects an order with `OrderStatus::Pending` as it is pending for too long.
 ///
-/// This function always returns an error.
+/// This function always returns an error, as the order can not be placed anymore.
 ///
 /// `collection` - MongoDB collection to modify the order status in.
 /// `id` - UUID of order to set the status to rejected.
-async fn set_status_rejected_in_mongodb(collection: &Collection<Order>, id: Uuid) -> Result<()> {
+async fn reject_pending_timeout_exceeded(collection: &Collection<Order>, id: Uuid) -> Result<()> {
+    let result = set_status_rejected_in_mongodb(
+        collection,
+        id,
+        OrderStatus::Pending,
+        RejectionReason::PendingTimeoutExceeded,
+    )
+    .await;
+    let message = match result {
+        Ok(true) => format!(
+            "Order of id: `{}` was rejected as it is `OrderStatus::Pending` for too long.",
+            id
+        ),
+        Ok(false) => format!(
+            "Order of id: `{}` must be `OrderStatus::Pending` to be able to be placed. Order was already placed or rejected concurrently.",
+            id
+        ),
+        Err(_) => format!("Order should be rejected as it is `OrderStatus::Pending` for too long. Rejecting order of id: `{}` failed in MongoDB.", id),
+    };
+    Err(Error::new(message))
+}
+
+/// Updates order to `OrderStatus::Rejected` with a rejection reason in MongoDB.
+///
+/// The update only matches the order while it has the expected status, which makes repeated rejections idempotent.
+/// Returns whether the order was rejected by this update, `false` if the order did not have the expected status.
+///
+/// * `collection` - MongoDB collection to modify the order status in.
+/// * `id` - UUID of order to set the status to rejected.
+/// * `expected_status` - Status the order must have to be rejected.
+/// * `rejection_reason` - Reason of the order rejection.
+pub async fn set_status_rejected_in_mongodb(
+    collection: &Collection<Order>,
+    id: Uuid,
+    expected_status: OrderStatus,
+    rejection_reason: RejectionReason,
+) -> Result<bool> {
+    let current_timestamp = DateTime::now();
     let result = collection
         .update_one(
-            doc! {"_id": id },
-            doc! {"$set": {"order_status": OrderStatus::Rejected}},
+            doc! {"_id": id, "order_status": expected_status },
+            doc! {
+                "$set": {"order_status": OrderStatus::Rejected, "rejection_reason": rejection_reason, "last_updated_at": current_timestamp},
+                "$push": {"status_history": StatusHistoryEntry::new(OrderStatus::Rejected, current_timestamp)},
+            },
             None,
         )
         .await;
     match result {
+        Ok(update_result) if update_result.matched_count == 0 => Ok(false),
         Ok(_) => {
-            let message = format!(
-                "Order of id: `{}` was rejected as it is `OrderStatus::Pending` for too long.",
-                id
-            );
-            return Err(Error::new(message));
+            increment_rejected_orders(rejection_reason);
+            Ok(true)
         }
         Err(_) => {
-            let message = format!("Order should be rejected as it is `OrderStatus::Pending` for too long. Rejecting order of id: `{}` failed in MongoDB.", id);
-            return Err(Error::new(message));
+            let message = format!("Rejecting order of id: `{}` failed in MongoDB.", id);
+            Err(Error::new(message))
         }
     }
 }
 
 /// Checks if foreign types exist (MongoDB database populated with events).
 async fn validate_order_input(db_client: &Database, input: &CreateOrderInput) -> Result<()> {
-    let user_collection: mongodb::Collection<User> = db_client.collection::<User>("users");
+    let user_collection: mongodb::Collection<User> = db_client.collection::<User>(USERS);
     validate_object(&user_collection, input.user_id).await?;
     validate_order_items(&db_client, &input.order_item_inputs).await?;
     validate_addresses(&db_client, &input).await?;
+    validate_payment_information(db_client, input.payment_information_id, input.user_id).await?;
     Ok(())
 }
 
+/// Checks if payment information is in the system (MongoDB database populated with events) and belongs to the user.
+///
+/// Used before creating orders.
+///
+/// * `db_client` - MongoDB database client.
+/// * `payment_information_id` - UUID of payment information to validate.
+/// * `user_id` - UUID of user which should own the payment information.
+async fn validate_payment_information(
+    db_client: &Database,
+    payment_information_id: Uuid,
+    user_id: Uuid,
+) -> Result<()> {
+    let collection: Collection<PaymentInformation> =
+        db_client.collection::<PaymentInformation>(PAYMENT_INFORMATIONS);
+    let payment_information = query_object(&collection, payment_information_id).await?;
+    match payment_information.user_id == user_id {
+        true => Ok(()),
+        false => {
+            let message = format!(
+                "Payment information with UUID: `{}` does not belong to user with UUID: `{}`.",
+                payment_information_id, user_id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
 /// Checks if all order item parameters are the system (MongoDB database populated with events).
 ///
 /// Used before creating orders.
@@ -248,16 +1413,84 @@ async fn validate_order_items(
     order_item_inputs: &BTreeSet<OrderItemInput>,
 ) -> Result<()> {
     let shipment_method_collection: mongodb::Collection<ShipmentMethod> =
-        db_client.collection::<ShipmentMethod>("shipment_methods");
-    let shipment_method_ids = order_item_inputs
+        db_client.collection::<ShipmentMethod>(SHIPMENT_METHODS);
+    let shipment_method_ids: Vec<Uuid> = order_item_inputs
         .iter()
         .map(|order_item_input| order_item_input.shipment_method_id)
         .collect();
-    validate_objects(&shipment_method_collection, shipment_method_ids).await?;
+    validate_objects(&shipment_method_collection, shipment_method_ids.clone()).await?;
+    validate_shipment_methods_enabled(&shipment_method_collection, &shipment_method_ids).await?;
+    validate_shipment_methods_compatible(&shipment_method_ids)?;
     validate_coupons(&db_client, &order_item_inputs).await?;
     Ok(())
 }
 
+/// Checks if shipment methods are enabled and can therefore be selected for new orders.
+///
+/// Used before creating orders.
+async fn validate_shipment_methods_enabled(
+    collection: &Collection<ShipmentMethod>,
+    shipment_method_ids: &Vec<Uuid>,
+) -> Result<()> {
+    let shipment_methods = query_objects(collection, shipment_method_ids).await?;
+    match shipment_methods
+        .values()
+        .find(|shipment_method| !shipment_method.enabled)
+    {
+        Some(shipment_method) => {
+            let message = format!(
+                "Shipment method of UUID: `{}` is disabled and can not be selected.",
+                shipment_method._id
+            );
+            Err(Error::new(message))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Checks that the shipment methods can be combined into one delivery, otherwise returns the conflicting shipment methods.
+///
+/// All order items of an order are delivered to the shipment address of the order.
+/// Allows all combinations if no incompatible shipment methods are configured.
+/// The incompatible shipment methods apply to every destination, as user addresses are only synchronized with their UUIDs and the destination country of an order is therefore unknown.
+///
+/// * `shipment_method_ids` - UUIDs of shipment methods of the order items of an order.
+fn validate_shipment_methods_compatible(shipment_method_ids: &[Uuid]) -> Result<()> {
+    let conflicting_shipment_method_ids: Vec<(Uuid, Uuid)> = incompatible_shipment_methods()
+        .into_iter()
+        .filter(|(id, other_id)| {
+            shipment_method_ids.contains(id) && shipment_method_ids.contains(other_id)
+        })
+        .collect();
+    match conflicting_shipment_method_ids.len() {
+        0 => Ok(()),
+        _ => {
+            let message = format!(
+                "Shipment methods of UUIDs: `{:?}` can not be combined into one delivery.",
+                conflicting_shipment_method_ids
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
+/// Reads the pairs of shipment methods, which can not be combined into one delivery, from `$INCOMPATIBLE_SHIPMENT_METHODS`.
+///
+/// Pairs are comma-separated and consist of two shipment method UUIDs separated by `:`.
+/// Malformed pairs are ignored, defaults to no incompatible shipment methods.
+fn incompatible_shipment_methods() -> Vec<(Uuid, Uuid)> {
+    env::var("INCOMPATIBLE_SHIPMENT_METHODS")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|pair| {
+            let (id, other_id) = pair.trim().split_once(':')?;
+            let id = Uuid::parse_str(id.trim()).ok()?;
+            let other_id = Uuid::parse_str(other_id.trim()).ok()?;
+            Some((id, other_id))
+        })
+        .collect()
+}
+
 /// Checks if coupons are in the system (MongoDB database populated with events).
 ///
 /// Used before creating orders.
@@ -265,21 +1498,57 @@ async fn validate_coupons(
     db_client: &Database,
     order_item_inputs: &BTreeSet<OrderItemInput>,
 ) -> Result<()> {
-    let coupon_collection: mongodb::Collection<Coupon> = db_client.collection::<Coupon>("coupons");
+    let coupon_collection: mongodb::Collection<Coupon> = db_client.collection::<Coupon>(COUPONS);
     let coupon_ids: Vec<Uuid> = order_item_inputs
         .iter()
         .map(|order_item_input| order_item_input.coupon_ids.clone())
         .flatten()
         .collect();
+    verify_coupons_unique_across_order_items(&coupon_ids)?;
     validate_objects(&coupon_collection, coupon_ids).await
 }
 
+/// Verifies that each coupon is referenced by at most one order item of an order, otherwise returns the coupons referenced multiple times.
+///
+/// A coupon is redeemed once per order. Referencing a coupon by multiple order items would apply its discount multiple times.
+///
+/// * `coupon_ids` - UUIDs of coupons of all order items of an order, containing a UUID once per referencing order item.
+fn verify_coupons_unique_across_order_items(coupon_ids: &[Uuid]) -> Result<()> {
+    let mut unique_coupon_ids: HashSet<Uuid> = HashSet::new();
+    let duplicate_coupon_ids: BTreeSet<Uuid> = coupon_ids
+        .iter()
+        .filter(|id| !unique_coupon_ids.insert(**id))
+        .cloned()
+        .collect();
+    match duplicate_coupon_ids.len() {
+        0 => Ok(()),
+        _ => {
+            let message = format!(
+                "Coupons of UUIDs: `{:?}` are referenced by multiple order items, a coupon can only be applied to one order item of an order.",
+                duplicate_coupon_ids
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
 /// Checks if addresses are registered under the user (MongoDB database populated with events).
 ///
+/// The shipment address of gift orders does not need to be registered under the user, but under the gift recipient.
+/// Gift orders with a gift recipient address are shipped to it, their shipment address is therefore not validated.
 /// Used before creating orders.
 async fn validate_addresses(db_client: &Database, input: &CreateOrderInput) -> Result<()> {
-    let user_collection: mongodb::Collection<User> = db_client.collection::<User>("users");
-    validate_user_address(&user_collection, input.shipment_address_id, input.user_id).await?;
+    let user_collection: mongodb::Collection<User> = db_client.collection::<User>(USERS);
+    match (input.is_gift, &input.gift_recipient_address) {
+        (false, _) => {
+            validate_user_address(&user_collection, input.shipment_address_id, input.user_id)
+                .await?
+        }
+        (true, None) => {
+            validate_registered_address(&user_collection, input.shipment_address_id).await?
+        }
+        (true, Some(_)) => (),
+    }
     validate_user_address(&user_collection, input.invoice_address_id, input.user_id).await
 }
 
@@ -287,13 +1556,16 @@ async fn validate_addresses(db_client: &Database, input: &CreateOrderInput) -> R
 ///
 /// Used before creating orders.
 /// Each order can only contain an order item with a specific product variant once.
+/// Returns the order items together with their total shipment fees.
 async fn create_internal_order_items<'a>(
     ctx: &Context<'a>,
     input: &CreateOrderInput,
     current_timestamp: DateTime,
-) -> Result<Vec<OrderItem>> {
+) -> Result<(Vec<OrderItem>, u64)> {
     let db_client = ctx.data::<Database>()?;
     let authorized_header = ctx.data::<AuthorizedUserHeader>()?;
+    let correlation_id = ctx.data::<CorrelationId>()?;
+    let rounding_strategy = ctx.data::<RoundingStrategy>()?;
     let (
         counts_by_product_variant_ids,
         order_item_inputs_by_product_variant_ids,
@@ -301,22 +1573,28 @@ async fn create_internal_order_items<'a>(
         product_variant_versions_by_product_variant_ids,
         tax_rate_versions_by_product_variant_ids,
         discounts_by_product_variant_ids,
-    ) = query_or_obtain_order_item_attributes(authorized_header, input, db_client).await?;
+        shipping_total,
+    ) = query_or_obtain_order_item_attributes(authorized_header, correlation_id, input, db_client)
+        .await?;
     let internal_order_items = zip_to_internal_order_items(
         order_item_inputs_by_product_variant_ids,
         product_variants_by_product_variant_ids,
         product_variant_versions_by_product_variant_ids,
-        tax_rate_versions_by_product_variant_ids,
+        PricingByProductVariantIds {
+            tax_rate_versions_by_product_variant_ids,
+            discounts_by_product_variant_ids,
+            rounding_strategy: *rounding_strategy,
+        },
         counts_by_product_variant_ids,
-        discounts_by_product_variant_ids,
         current_timestamp,
     )?;
-    Ok(internal_order_items)
+    Ok((internal_order_items, shipping_total))
 }
 
-/// Queries or obtains the attributes necessary for order item construction.
+/// Queries or obtains the attributes necessary for order item construction, including the total shipment fees of the order items.
 async fn query_or_obtain_order_item_attributes(
     authorized_header: &AuthorizedUserHeader,
+    correlation_id: &CorrelationId,
     input: &CreateOrderInput,
     db_client: &Database,
 ) -> Result<
@@ -327,40 +1605,56 @@ async fn query_or_obtain_order_item_attributes(
         HashMap<Uuid, ProductVariantVersion>,
         HashMap<Uuid, TaxRateVersion>,
         HashMap<Uuid, BTreeSet<Discount>>,
+        u64,
     ),
     Error,
 > {
     let (counts_by_product_variant_ids, order_item_inputs_by_product_variant_ids) =
-        query_counts_by_product_variant_ids(authorized_header, &input).await?;
+        query_counts_by_product_variant_ids(authorized_header, correlation_id, input).await?;
+    verify_item_quantity_cap(&counts_by_product_variant_ids)?;
     let product_variant_ids: Vec<Uuid> = counts_by_product_variant_ids.keys().cloned().collect();
     let product_variants_by_product_variant_ids: HashMap<Uuid, ProductVariant> =
-        query_product_variants_by_product_variant_ids(db_client, &product_variant_ids).await?;
+        query_product_variants_by_product_variant_ids(db_client, &product_variant_ids, true)
+            .await?;
     let product_variant_versions_by_product_variant_ids =
         query_product_variant_versions_by_product_variant_ids(
             &product_variants_by_product_variant_ids,
         )
         .await;
-    check_product_variant_availability(&product_variant_ids, &counts_by_product_variant_ids)
-        .await?;
     let tax_rate_versions_by_product_variant_ids = query_tax_rate_versions_by_product_variant_ids(
         db_client,
         &product_variant_versions_by_product_variant_ids,
     )
     .await?;
-    let discounts_by_product_variant_ids = query_discounts_by_product_variant_ids(
-        input.user_id,
-        &order_item_inputs_by_product_variant_ids,
-        &product_variant_ids,
-        &product_variant_versions_by_product_variant_ids,
-        &counts_by_product_variant_ids,
-    )
-    .await?;
-    let _shipment_fees = query_shipment_fees(
-        &order_item_inputs_by_product_variant_ids,
-        &product_variant_versions_by_product_variant_ids,
-        &counts_by_product_variant_ids,
-    )
-    .await?;
+    let discounts_by_product_variant_ids = match discounts_enabled() {
+        true => {
+            query_discounts_by_product_variant_ids(
+                input.user_id,
+                &order_item_inputs_by_product_variant_ids,
+                &product_variant_ids,
+                &product_variant_versions_by_product_variant_ids,
+                &counts_by_product_variant_ids,
+                correlation_id,
+            )
+            .await?
+        }
+        false => product_variant_ids
+            .iter()
+            .map(|id| (*id, BTreeSet::new()))
+            .collect(),
+    };
+    let shipment_fees = match shipment_fees_enabled() {
+        true => {
+            query_shipment_fees(
+                &order_item_inputs_by_product_variant_ids,
+                &product_variant_versions_by_product_variant_ids,
+                &counts_by_product_variant_ids,
+                correlation_id,
+            )
+            .await?
+        }
+        false => 0,
+    };
     Ok((
         counts_by_product_variant_ids,
         order_item_inputs_by_product_variant_ids,
@@ -368,19 +1662,56 @@ async fn query_or_obtain_order_item_attributes(
         product_variant_versions_by_product_variant_ids,
         tax_rate_versions_by_product_variant_ids,
         discounts_by_product_variant_ids,
+        shipment_fees,
     ))
 }
 
+/// Defines if applicable discounts are queried from the discount service.
+///
+/// Otherwise order items are created without discounts, e.g. in deployments without a discount service.
+/// Read from `$DISCOUNTS_ENABLED`, defaults to `true`.
+fn discounts_enabled() -> bool {
+    env::var("DISCOUNTS_ENABLED")
+        .ok()
+        .and_then(|enabled| enabled.parse::<bool>().ok())
+        .unwrap_or(true)
+}
+
+/// Defines if shipment fees are queried from the shipment service.
+///
+/// Otherwise orders are created with a shipping total of `0`, e.g. in deployments without a shipment service.
+/// Read from `$SHIPMENT_FEES_ENABLED`, defaults to `true`.
+fn shipment_fees_enabled() -> bool {
+    env::var("SHIPMENT_FEES_ENABLED")
+        .ok()
+        .and_then(|enabled| enabled.parse::<bool>().ok())
+        .unwrap_or(true)
+}
+
+/// Pricing inputs of order items by product variant UUIDs, which are zipped to an `OrderItemPricing` for each order item.
+struct PricingByProductVariantIds {
+    /// Current tax rate versions by product variant UUIDs.
+    tax_rate_versions_by_product_variant_ids: HashMap<Uuid, TaxRateVersion>,
+    /// Applicable discounts by product variant UUIDs.
+    discounts_by_product_variant_ids: HashMap<Uuid, BTreeSet<Discount>>,
+    /// Strategy to round discounted prices to minor units.
+    rounding_strategy: RoundingStrategy,
+}
+
 /// Zips hash maps which contain the required attributes for construction to order items.
 fn zip_to_internal_order_items(
     order_item_inputs_by_product_variant_ids: HashMap<Uuid, OrderItemInput>,
     product_variants_by_product_variant_ids: HashMap<Uuid, ProductVariant>,
     product_variant_versions_by_product_variant_ids: HashMap<Uuid, ProductVariantVersion>,
-    tax_rate_versions_by_product_variant_ids: HashMap<Uuid, TaxRateVersion>,
+    pricing_by_product_variant_ids: PricingByProductVariantIds,
     counts_by_product_variant_ids: HashMap<Uuid, u64>,
-    discounts_by_product_variant_ids: HashMap<Uuid, BTreeSet<Discount>>,
     current_timestamp: DateTime,
 ) -> Result<Vec<OrderItem>> {
+    let PricingByProductVariantIds {
+        tax_rate_versions_by_product_variant_ids,
+        discounts_by_product_variant_ids,
+        rounding_strategy,
+    } = pricing_by_product_variant_ids;
     product_variants_by_product_variant_ids
         .iter()
         .map(|(id, product_variant)| {
@@ -409,9 +1740,12 @@ fn zip_to_internal_order_items(
                 order_item_input,
                 product_variant,
                 product_variant_version,
-                tax_rate_version,
+                OrderItemPricing {
+                    tax_rate_version,
+                    internal_discounts,
+                    rounding_strategy,
+                },
                 *count,
-                internal_discounts,
                 current_timestamp,
             );
             Ok(order_item)
@@ -419,6 +1753,43 @@ fn zip_to_internal_order_items(
         .collect::<Result<Vec<OrderItem>>>()
 }
 
+/// Defines if the bodies of outbound GraphQL requests and their responses are logged at debug level.
+///
+/// Read from `$LOG_OUTBOUND_BODIES`, defaults to `false`.
+fn outbound_bodies_logged() -> bool {
+    env::var("LOG_OUTBOUND_BODIES")
+        .ok()
+        .and_then(|logged| logged.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Deserializes the response of an outbound GraphQL request to the inventory, discount or shipment service.
+///
+/// Logs the serialized request body and the raw response body at debug level if `$LOG_OUTBOUND_BODIES` is enabled.
+/// Headers are never logged, which keeps the `Authorized-User` header out of the logs.
+/// These request bodies contain no payment authorization, e.g. no CVC.
+///
+/// * `service` - Name of the service the request was sent to.
+/// * `request_body` - Body of the sent request.
+/// * `res` - Response to deserialize.
+async fn deserialize_outbound_response<V: Serialize, T: DeserializeOwned>(
+    service: &str,
+    request_body: &QueryBody<V>,
+    res: reqwest::Response,
+) -> Result<Response<T>> {
+    if !outbound_bodies_logged() {
+        return Ok(res.json().await?);
+    }
+    let serialized_request_body = serde_json::to_string(request_body)?;
+    let status = res.status();
+    let raw_response_body = res.text().await?;
+    debug!(
+        "Outbound request to `{}` service with body: `{}` responded with status `{}` and body: `{}`.",
+        service, serialized_request_body, status, raw_response_body
+    );
+    Ok(serde_json::from_str(&raw_response_body)?)
+}
+
 // Defines a custom scalar from GraphQL schema.
 type _Any = Representation;
 
@@ -442,6 +1813,7 @@ struct Representation {
 async fn check_product_variant_availability(
     product_variant_ids: &Vec<Uuid>,
     counts_by_product_variant_ids: &HashMap<Uuid, u64>,
+    correlation_id: &CorrelationId,
 ) -> Result<()> {
     let representations = product_variant_ids
         .iter()
@@ -457,24 +1829,52 @@ async fn check_product_variant_availability(
     let client = reqwest::Client::new();
 
     let res = client
-        .post("http://localhost:3500/v1.0/invoke/inventory/method/graphql")
+        .post(dapr_url("v1.0/invoke/inventory/method/graphql"))
         .json(&request_body)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
         .send()
         .await?;
     let response_body: Response<get_unreserved_product_item_counts::ResponseData> =
-        res.json().await?;
+        deserialize_outbound_response("inventory", &request_body, res).await?;
     let response_data: get_unreserved_product_item_counts::ResponseData =
         response_body.data.ok_or(Error::new(
             "Response data of `check_product_variant_availability` query is empty.",
         ))?;
-    let stock_counts_by_product_variant_ids =
+    let mut stock_counts_by_product_variant_ids =
         build_stock_counts_by_product_variant_from_response_data(response_data)?;
+    if shopping_cart_items_soft_reserved() {
+        include_own_soft_reservations(
+            &mut stock_counts_by_product_variant_ids,
+            counts_by_product_variant_ids,
+        );
+    }
     calculate_availability_of_product_variant_ids(
         &stock_counts_by_product_variant_ids,
         &counts_by_product_variant_ids,
     )
 }
 
+/// Checks if the product variants of order items are available in the requested quantities.
+///
+/// * `internal_order_items` - Order items to check availability of.
+/// * `correlation_id` - Correlation id to forward to the inventory service.
+async fn check_order_items_availability(
+    internal_order_items: &[OrderItem],
+    correlation_id: &CorrelationId,
+) -> Result<()> {
+    let counts_by_product_variant_ids: HashMap<Uuid, u64> = internal_order_items
+        .iter()
+        .map(|order_item| (order_item.product_variant._id, order_item.count))
+        .collect();
+    let product_variant_ids: Vec<Uuid> = counts_by_product_variant_ids.keys().cloned().collect();
+    check_product_variant_availability(
+        &product_variant_ids,
+        &counts_by_product_variant_ids,
+        correlation_id,
+    )
+    .await
+}
+
 /// Remaps the result type of the GraphQL `_entities` query retrieving stock counts for product variants.
 fn build_stock_counts_by_product_variant_from_response_data(
     response_data: get_unreserved_product_item_counts::ResponseData,
@@ -501,31 +1901,124 @@ fn build_stock_counts_by_product_variant_from_response_data(
         }).collect()
 }
 
+/// Defines if the inventory service soft-reserves the items in the shopping cart of a user.
+///
+/// Read from `$INVENTORY_SOFT_RESERVES_SHOPPING_CART_ITEMS`, defaults to `false`.
+fn shopping_cart_items_soft_reserved() -> bool {
+    env::var("INVENTORY_SOFT_RESERVES_SHOPPING_CART_ITEMS")
+        .ok()
+        .and_then(|soft_reserved| soft_reserved.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
+/// Adds the soft reservations of the ordering user to the unreserved stock counts.
+///
+/// If the inventory service soft-reserves shopping cart items, the unreserved stock counts exclude the items of the users own shopping cart.
+/// As the order items are obtained from the shopping cart of the user, their counts are the users own soft reservations.
+/// This ensures that a user is never blocked by their own reservation.
+///
+/// * `stock_counts_by_product_variant_ids` - Unreserved stock counts to add the own soft reservations to.
+/// * `own_reserved_counts_by_product_variant_ids` - Counts soft-reserved by the shopping cart of the user.
+fn include_own_soft_reservations(
+    stock_counts_by_product_variant_ids: &mut HashMap<Uuid, u64>,
+    own_reserved_counts_by_product_variant_ids: &HashMap<Uuid, u64>,
+) {
+    for (id, stock_count) in stock_counts_by_product_variant_ids.iter_mut() {
+        if let Some(own_reserved_count) = own_reserved_counts_by_product_variant_ids.get(id) {
+            *stock_count += own_reserved_count;
+        }
+    }
+}
+
+/// Error code of the error for product variants, which are not available in the requested quantities.
+const PRODUCT_VARIANTS_UNAVAILABLE_ERROR_CODE: &str = "PRODUCT_VARIANTS_UNAVAILABLE";
+
+/// Product variant which is not available in the requested quantity.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UnavailableProductVariant {
+    /// UUID of the product variant.
+    product_variant_id: Uuid,
+    /// Requested count of product items of the product variant.
+    requested_count: u64,
+    /// Count of product items of the product variant available in stock.
+    available_count: u64,
+}
+
 /// Calculates the availability based on the actual and expected stock counts based on the product variant UUIDs.
 ///
 /// The expected amount or more product items need to be in stock for a product variant to be counted as available.
 /// All product variants need to be available for this function to pass without an error.
+/// The error lists each unavailable product variant with its requested and available count in the `unavailableProductVariants` error extension.
 fn calculate_availability_of_product_variant_ids(
     stock_counts_by_product_variant_ids: &HashMap<Uuid, u64>,
     expected_stock_counts_by_product_variant_ids: &HashMap<Uuid, u64>,
 ) -> Result<()> {
-    let availabilites: Vec<bool> = expected_stock_counts_by_product_variant_ids
-        .iter()
-        .map(|(id, expected_count)| {
-            let error = build_hash_map_error(expected_stock_counts_by_product_variant_ids, *id);
-            let count = stock_counts_by_product_variant_ids.get(id).ok_or(error)?;
-            Ok(*count >= *expected_count)
-        })
-        .collect::<Result<Vec<bool>>>()?;
-    match availabilites
-        .into_iter()
-        .all(|is_available| is_available == true)
-    {
-        true => Ok(()),
-        false => Err(Error::new(
-            "Not all requested product variants are available.",
-        )),
+    let mut unavailable_product_variants: Vec<UnavailableProductVariant> =
+        expected_stock_counts_by_product_variant_ids
+            .iter()
+            .map(|(id, expected_count)| {
+                let error = build_hash_map_error(expected_stock_counts_by_product_variant_ids, *id);
+                let count = stock_counts_by_product_variant_ids.get(id).ok_or(error)?;
+                Ok(UnavailableProductVariant {
+                    product_variant_id: *id,
+                    requested_count: *expected_count,
+                    available_count: *count,
+                })
+            })
+            .collect::<Result<Vec<UnavailableProductVariant>>>()?
+            .into_iter()
+            .filter(|product_variant| {
+                product_variant.available_count < product_variant.requested_count
+            })
+            .collect();
+    if unavailable_product_variants.is_empty() {
+        return Ok(());
     }
+    unavailable_product_variants.sort_by_key(|product_variant| product_variant.product_variant_id);
+    Err(build_product_variants_unavailable_error(
+        &unavailable_product_variants,
+    ))
+}
+
+/// Checks if an error describes product variants, which are not available in the requested quantities.
+///
+/// Other errors of the availability check, e.g. network or deserialization errors, do not match.
+///
+/// * `error` - Error of the availability check.
+fn is_product_variants_unavailable(error: &Error) -> bool {
+    error
+        .extensions
+        .as_ref()
+        .and_then(|extensions| extensions.get("code"))
+        .is_some_and(|code| *code == Value::from(PRODUCT_VARIANTS_UNAVAILABLE_ERROR_CODE))
+}
+
+/// Builds the error for product variants which are not available in the requested quantities.
+///
+/// Carries the `PRODUCT_VARIANTS_UNAVAILABLE` error code and the unavailable product variants, so that clients can tell which quantities to reduce.
+///
+/// * `unavailable_product_variants` - Product variants which are not available in the requested quantities.
+fn build_product_variants_unavailable_error(
+    unavailable_product_variants: &Vec<UnavailableProductVariant>,
+) -> Error {
+    let product_variant_ids: Vec<Uuid> = unavailable_product_variants
+        .iter()
+        .map(|product_variant| product_variant.product_variant_id)
+        .collect();
+    let message = format!(
+        "Not all requested product variants are available, product variants of UUIDs: `{:?}` are not available in the requested quantities.",
+        product_variant_ids
+    );
+    let unavailable_product_variants =
+        to_value(unavailable_product_variants).unwrap_or(Value::Null);
+    Error::new(message).extend_with(|_, extensions| {
+        extensions.set("code", PRODUCT_VARIANTS_UNAVAILABLE_ERROR_CODE);
+        extensions.set(
+            "unavailableProductVariants",
+            unavailable_product_variants.clone(),
+        );
+    })
 }
 
 // Defines a custom scalar from GraphQL schema.
@@ -543,6 +2036,7 @@ struct GetShoppingCartProductVariantIdsAndCounts;
 /// Queries product variants from shopping cart item ids from shopping cart service.
 async fn query_counts_by_product_variant_ids(
     authorized_user_header: &AuthorizedUserHeader,
+    correlation_id: &CorrelationId,
     input: &CreateOrderInput,
 ) -> Result<(HashMap<Uuid, u64>, HashMap<Uuid, OrderItemInput>)> {
     let representations = vec![Representation {
@@ -554,22 +2048,27 @@ async fn query_counts_by_product_variant_ids(
     let request_body = GetShoppingCartProductVariantIdsAndCounts::build_query(variables);
     let client = reqwest::Client::new();
 
-    let authorized_user_header_string = serde_json::to_string(authorized_user_header)?;
+    let authorized_user_header_string = authorized_user_header.to_forwarded_header_value()?;
     let res = client
-        .post("http://localhost:3500/v1.0/invoke/shoppingcart/method/")
+        .post(dapr_url("v1.0/invoke/shoppingcart/method/"))
         .json(&request_body)
         .header("Authorized-User", authorized_user_header_string)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
         .send()
         .await?;
     let response_body: Response<get_shopping_cart_product_variant_ids_and_counts::ResponseData> =
         res.json().await?;
     let message = "Response data of `query_counts_by_product_variant_ids` query is empty.";
-    let mut response_data: get_shopping_cart_product_variant_ids_and_counts::ResponseData =
+    let response_data: get_shopping_cart_product_variant_ids_and_counts::ResponseData =
         response_body.data.ok_or(Error::new(message))?;
-    let shopping_cart_response_data = response_data.entities.remove(0).ok_or(message)?;
+    let shopping_cart_response_data = extract_shopping_cart_entity(response_data, input.user_id)?;
 
     let ids_and_counts_by_shopping_cart_item_ids =
         into_ids_and_counts_by_shopping_cart_item_ids(shopping_cart_response_data)?;
+    verify_product_variants_unique(
+        &input.order_item_inputs,
+        &ids_and_counts_by_shopping_cart_item_ids,
+    )?;
     let counts_by_product_variant_ids = build_counts_by_product_variant_ids(
         &input.order_item_inputs,
         &ids_and_counts_by_shopping_cart_item_ids,
@@ -584,6 +2083,25 @@ async fn query_counts_by_product_variant_ids(
     ))
 }
 
+/// Extracts the entity of the user from the response data of the shopping cart service.
+///
+/// Returns an error instead of panicking if the shopping cart service returns no or a `null` entity.
+///
+/// * `response_data` - Response data of the shopping cart service.
+/// * `user_id` - UUID of the user whose shopping cart is queried.
+fn extract_shopping_cart_entity(
+    response_data: get_shopping_cart_product_variant_ids_and_counts::ResponseData,
+    user_id: Uuid,
+) -> Result<get_shopping_cart_product_variant_ids_and_counts::GetShoppingCartProductVariantIdsAndCountsEntities>{
+    let message = format!("Shopping cart not found for user of UUID: `{}`.", user_id);
+    response_data
+        .entities
+        .into_iter()
+        .next()
+        .flatten()
+        .ok_or(Error::new(message))
+}
+
 // Unwraps enum and maps the result to a hash map of shopping cart item ids as keys and `(product_variant_id, count)` as values.
 fn into_ids_and_counts_by_shopping_cart_item_ids(
     ids_and_counts_enum: get_shopping_cart_product_variant_ids_and_counts::GetShoppingCartProductVariantIdsAndCountsEntities,
@@ -600,6 +2118,32 @@ fn into_ids_and_counts_by_shopping_cart_item_ids(
     }
 }
 
+/// Verifies that no two order item inputs reference shopping cart items of the same product variant.
+///
+/// Each order can only contain an order item with a specific product variant once.
+fn verify_product_variants_unique(
+    order_item_inputs: &BTreeSet<OrderItemInput>,
+    ids_and_counts: &HashMap<Uuid, (Uuid, u64)>,
+) -> Result<()> {
+    let mut shopping_cart_item_ids_by_product_variant_ids: HashMap<Uuid, Uuid> = HashMap::new();
+    for order_item_input in order_item_inputs {
+        if let Some((product_variant_id, _)) =
+            ids_and_counts.get(&order_item_input.shopping_cart_item_id)
+        {
+            if let Some(other_shopping_cart_item_id) = shopping_cart_item_ids_by_product_variant_ids
+                .insert(*product_variant_id, order_item_input.shopping_cart_item_id)
+            {
+                let message = format!(
+                    "Shopping cart items of UUIDs: `{}` and `{}` reference the same product variant of UUID: `{}`. Each product variant can only be contained in an order once.",
+                    other_shopping_cart_item_id, order_item_input.shopping_cart_item_id, product_variant_id
+                );
+                return Err(Error::new(message));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Filters shopping cart items: `ids_and_counts` to map to `order_item_inputs`.
 /// Builds hash map which maps product variant ids to counts.
 fn build_counts_by_product_variant_ids(
@@ -639,24 +2183,33 @@ fn build_order_item_inputs_by_product_variant_ids(
 
 /// Obtains product variants from product variant UUIDs.
 ///
-/// Filters product variants which are non-publicly-visible.
+/// Filters product variants which are non-publicly-visible if `visible_only` is set.
+/// Order creation must set `visible_only`, read paths of existing orders must not, as they would hide purchased product variants.
+///
+/// * `db_client` - MongoDB database client.
+/// * `product_variant_ids` - UUIDs of product variants to obtain.
+/// * `visible_only` - Whether non-publicly-visible product variants are filtered.
 async fn query_product_variants_by_product_variant_ids(
     db_client: &Database,
     product_variant_ids: &Vec<Uuid>,
+    visible_only: bool,
 ) -> Result<HashMap<Uuid, ProductVariant>> {
     let collection: Collection<ProductVariant> =
-        db_client.collection::<ProductVariant>("product_variants");
+        db_client.collection::<ProductVariant>(PRODUCT_VARIANTS);
     let product_variants_by_product_variant_ids_unfiltered =
         query_objects(&collection, product_variant_ids).await?;
     let product_variants_by_product_variant_ids =
         product_variants_by_product_variant_ids_unfiltered
             .into_iter()
-            .filter(|(_, p)| p.is_publicly_visible)
+            .filter(|(_, p)| !visible_only || p.is_publicly_visible)
             .collect();
     Ok(product_variants_by_product_variant_ids)
 }
 
 /// Obtains current product variant versions using product variants.
+///
+/// Order pricing always reflects the current product variant version at order creation, as synchronized by `catalog/product-variant-version/created` events.
+/// The shopping cart only references product variants, so a stale product variant version can not be passed by clients.
 async fn query_product_variant_versions_by_product_variant_ids(
     product_variants_by_product_variant_ids: &HashMap<Uuid, ProductVariant>,
 ) -> HashMap<Uuid, ProductVariantVersion> {
@@ -669,27 +2222,74 @@ async fn query_product_variant_versions_by_product_variant_ids(
 }
 
 /// Obtains current tax rate version for tax rate in product variant versions.
+///
+/// Rejects product variant versions with a deprecated tax rate.
 async fn query_tax_rate_versions_by_product_variant_ids(
     db_client: &Database,
     product_variant_versions_by_product_variant_ids: &HashMap<Uuid, ProductVariantVersion>,
 ) -> Result<HashMap<Uuid, TaxRateVersion>> {
-    let collection: Collection<TaxRate> = db_client.collection::<TaxRate>("tax_rates");
+    let collection: Collection<TaxRate> = db_client.collection::<TaxRate>(TAX_RATES);
     let tax_rate_ids: Vec<Uuid> = product_variant_versions_by_product_variant_ids
         .iter()
         .map(|(_, p)| p.tax_rate_id)
         .collect();
     let tax_rates = query_objects(&collection, &tax_rate_ids).await?;
+    let fallback_tax_rate = missing_tax_rate_fallback_rate();
     let tax_rate_versions_by_product_variant_ids = product_variant_versions_by_product_variant_ids
         .iter()
         .map(|(id, p)| {
-            let error = build_hash_map_error(&tax_rates, *id);
-            let tax_rate = tax_rates.get(&p.tax_rate_id).ok_or(error)?;
+            let tax_rate = match (tax_rates.get(&p.tax_rate_id), fallback_tax_rate) {
+                (Some(tax_rate), _) => tax_rate,
+                (None, Some(rate)) => {
+                    let tax_rate_version = TaxRateVersion {
+                        _id: p.tax_rate_id,
+                        rate,
+                        version: 0,
+                    };
+                    return Ok((*id, tax_rate_version));
+                }
+                (None, None) => {
+                    return Err(build_tax_rate_not_synchronized_error(p.tax_rate_id, *id))
+                }
+            };
+            if !tax_rate.active {
+                let message = format!(
+                    "Tax rate of UUID: `{}` of product variant of UUID: `{}` is deprecated.",
+                    tax_rate._id, id
+                );
+                return Err(Error::new(message));
+            }
             Ok((*id, tax_rate.current_version))
         })
         .collect::<Result<HashMap<Uuid, TaxRateVersion>>>()?;
     Ok(tax_rate_versions_by_product_variant_ids)
 }
 
+/// Reads the tax rate to fall back to for tax rates, which are not yet synchronized, from `$MISSING_TAX_RATE_FALLBACK_RATE`, e.g. `0.19`.
+///
+/// The fallback tax rate version has the UUID of the referenced tax rate and version `0`.
+/// Unset by default, order creation then fails with a retryable error.
+fn missing_tax_rate_fallback_rate() -> Option<f64> {
+    env::var("MISSING_TAX_RATE_FALLBACK_RATE")
+        .ok()
+        .and_then(|rate| rate.parse::<f64>().ok())
+}
+
+/// Builds the retryable error for a tax rate, which is referenced by a product variant version but not yet synchronized.
+///
+/// Carries the `TAX_RATE_NOT_SYNCHRONIZED` error code, as tax rate events can be delivered after the product variant version events referencing them.
+///
+/// * `tax_rate_id` - UUID of tax rate missing in MongoDB.
+/// * `product_variant_id` - UUID of product variant referencing the tax rate.
+fn build_tax_rate_not_synchronized_error(tax_rate_id: Uuid, product_variant_id: Uuid) -> Error {
+    let message = format!(
+        "Tax rate of UUID: `{}` of product variant of UUID: `{}` is not yet synchronized, try again later.",
+        tax_rate_id, product_variant_id
+    );
+    Error::new(message)
+        .extend_with(|_, extensions| extensions.set("code", "TAX_RATE_NOT_SYNCHRONIZED"))
+}
+
 #[derive(GraphQLQuery)]
 #[graphql(
     schema_path = "schemas_repo/discount.graphql",
@@ -706,6 +2306,7 @@ async fn query_discounts_by_product_variant_ids(
     product_variant_ids: &Vec<Uuid>,
     product_variant_versions_by_product_variant_ids: &HashMap<Uuid, ProductVariantVersion>,
     counts_by_product_variant_ids: &HashMap<Uuid, u64>,
+    correlation_id: &CorrelationId,
 ) -> Result<HashMap<Uuid, BTreeSet<Discount>>> {
     let find_applicable_discounts_product_variant_input =
         build_find_applicable_discounts_product_variant_input(
@@ -713,7 +2314,7 @@ async fn query_discounts_by_product_variant_ids(
             product_variant_ids,
             counts_by_product_variant_ids,
         )?;
-    let order_amount = calculate_order_amount(&product_variant_versions_by_product_variant_ids);
+    let order_amount = calculate_order_amount(product_variant_versions_by_product_variant_ids)?;
     let find_applicable_discounts_input = build_find_applicable_discounts_input(
         user_id,
         find_applicable_discounts_product_variant_input,
@@ -726,17 +2327,68 @@ async fn query_discounts_by_product_variant_ids(
     let client = reqwest::Client::new();
 
     let res = client
-        .post("http://localhost:3500/v1.0/invoke/discount/method/graphql")
+        .post(dapr_url("v1.0/invoke/discount/method/graphql"))
         .json(&request_body)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
         .send()
         .await?;
-    let response_body: Response<get_discounts::ResponseData> = res.json().await?;
+    let response_body: Response<get_discounts::ResponseData> =
+        deserialize_outbound_response("discount", &request_body, res).await?;
     let response_data: get_discounts::ResponseData = response_body.data.ok_or(Error::new(
         "Response data of `query_discounts` query is empty.",
     ))?;
     build_discounts_from_response_data(response_data, product_variant_ids)
 }
 
+/// Queries the applicable discounts of an existing order item from the discount service, e.g. after updating its quantity.
+///
+/// The order amount is calculated from all order items of the order, like when creating the order.
+///
+/// * `user_id` - UUID of the user owning the order.
+/// * `order_item` - Order item to query the discounts of.
+/// * `order_items` - All order items of the order, including `order_item`.
+/// * `correlation_id` - Correlation id to forward to the discount service.
+async fn query_order_item_discounts(
+    user_id: Uuid,
+    order_item: &OrderItem,
+    order_items: &[OrderItem],
+    correlation_id: &CorrelationId,
+) -> Result<BTreeSet<Discount>> {
+    let product_variant_id = order_item.product_variant._id;
+    let order_item_input = OrderItemInput {
+        shopping_cart_item_id: order_item.shopping_cart_item._id,
+        shipment_method_id: order_item.shipment_method._id,
+        coupon_ids: order_item.applied_coupon_ids.iter().cloned().collect(),
+    };
+    let order_item_inputs_by_product_variant_ids =
+        HashMap::from([(product_variant_id, order_item_input)]);
+    let product_variant_versions_by_product_variant_ids: HashMap<Uuid, ProductVariantVersion> =
+        order_items
+            .iter()
+            .map(|order_item| {
+                (
+                    order_item.product_variant._id,
+                    order_item.product_variant_version,
+                )
+            })
+            .collect();
+    let counts_by_product_variant_ids = HashMap::from([(product_variant_id, order_item.count)]);
+    let mut discounts_by_product_variant_ids = query_discounts_by_product_variant_ids(
+        user_id,
+        &order_item_inputs_by_product_variant_ids,
+        &vec![product_variant_id],
+        &product_variant_versions_by_product_variant_ids,
+        &counts_by_product_variant_ids,
+        correlation_id,
+    )
+    .await?;
+    let discounts_error =
+        build_hash_map_error(&discounts_by_product_variant_ids, product_variant_id);
+    discounts_by_product_variant_ids
+        .remove(&product_variant_id)
+        .ok_or(discounts_error)
+}
+
 /// Remaps the result type of the GraphQL `findApplicableDiscounts` query to the the according product variants.
 /// Converts the GraphQL client library generated discounts to the internally used discounts, which are GraphQL `SimpleObject`.
 fn build_discounts_from_response_data(
@@ -750,10 +2402,7 @@ fn build_discounts_from_response_data(
         response_data.find_applicable_discounts,
         &product_variant_ids,
     )?;
-    let simple_object_discounts = convert_graphql_client_lib_discounts_to_simple_object_discounts(
-        graphql_client_lib_discounts,
-    );
-    Ok(simple_object_discounts)
+    convert_graphql_client_lib_discounts_to_simple_object_discounts(graphql_client_lib_discounts)
 }
 
 /// Builds `get_discounts::FindApplicableDiscountsInput`, which is the following struct:
@@ -820,7 +2469,7 @@ fn build_find_applicable_discounts_product_variant_input(
             let find_applicable_discounts_product_variant_input =
                 get_discounts::FindApplicableDiscountsProductVariantInput {
                     product_variant_id: *id,
-                    count: i64::try_from(*count)?,
+                    count: convert_to_graphql_int(*count, "count")?,
                     coupon_ids,
                 };
             Ok::<get_discounts::FindApplicableDiscountsProductVariantInput, Error>(
@@ -832,6 +2481,8 @@ fn build_find_applicable_discounts_product_variant_input(
 }
 
 /// Remaps the result type of the GraphQL `findApplicableDiscounts` query to the the according product variants.
+///
+/// Product variants omitted by the discount service have no applicable discounts and are remapped to an empty discount set.
 fn remap_discounts_to_product_variants(
     discounts_for_product_variants_response_data: Vec<
         get_discounts::GetDiscountsFindApplicableDiscounts,
@@ -854,11 +2505,18 @@ fn remap_discounts_to_product_variants(
             map
         },
     );
-    product_variant_ids.iter().map(|id| {
-        let message = format!("Product variant of UUID: `{}` is not contained in the result which `findApplicableDiscounts` provides.", id);
-        let discounts =  discounts_for_product_variants.remove(id).ok_or(Error::new(message))?;
-        Ok((*id, discounts))
-    }).collect()
+    product_variant_ids
+        .iter()
+        .map(|id| {
+            let discounts = discounts_for_product_variants.remove(id).unwrap_or(
+                get_discounts::GetDiscountsFindApplicableDiscounts {
+                    product_variant_id: *id,
+                    discounts: vec![],
+                },
+            );
+            Ok((*id, discounts))
+        })
+        .collect()
 }
 
 /// Converts the GraphQL client library generated discounts to the internally used discounts, which are GraphQL `SimpleObject`.
@@ -866,7 +2524,8 @@ fn remap_discounts_to_product_variants(
 /// This enables the discounts to be retrivable from the GraphQL endpoints of this service.
 fn convert_graphql_client_lib_discounts_to_simple_object_discounts(
     graphql_client_lib_discounts: HashMap<Uuid, get_discounts::GetDiscountsFindApplicableDiscounts>,
-) -> HashMap<Uuid, BTreeSet<Discount>> {
+) -> Result<HashMap<Uuid, BTreeSet<Discount>>> {
+    let out_of_range_discounts_ignored = out_of_range_discounts_ignored();
     graphql_client_lib_discounts
         .into_iter()
         .map(|(id, discounts)| {
@@ -878,26 +2537,91 @@ fn convert_graphql_client_lib_discounts_to_simple_object_discounts(
                         Discount::from(discount)
                     },
                 )
-                .collect();
-            (id, discounts)
+                .filter_map(|discount| {
+                    validate_discount_multiplier(discount, id, out_of_range_discounts_ignored)
+                        .transpose()
+                })
+                .collect::<Result<BTreeSet<Discount>>>()?;
+            Ok((id, discounts))
         })
         .collect()
 }
 
+/// Validates that the multiplier of a discount is within `(0.0, 1.0]`.
+///
+/// A multiplier of `0.0` would make an order item free, a multiplier greater than `1.0` would increase its price.
+/// Out of range discounts are either ignored with a warning, which returns `None`, or rejected with an error.
+///
+/// * `discount` - Discount to validate.
+/// * `product_variant_id` - UUID of product variant the discount applies to.
+/// * `out_of_range_discounts_ignored` - Whether out of range discounts are ignored instead of rejected.
+fn validate_discount_multiplier(
+    discount: Discount,
+    product_variant_id: Uuid,
+    out_of_range_discounts_ignored: bool,
+) -> Result<Option<Discount>> {
+    if discount.discount > 0.0 && discount.discount <= 1.0 {
+        return Ok(Some(discount));
+    }
+    let message = format!(
+        "Discount of UUID: `{}` for product variant of UUID: `{}` has multiplier `{}`, which is not within `(0.0, 1.0]`.",
+        discount._id, product_variant_id, discount.discount
+    );
+    match out_of_range_discounts_ignored {
+        true => {
+            warn!("{} The discount is ignored.", message);
+            Ok(None)
+        }
+        false => Err(Error::new(message)),
+    }
+}
+
+/// Defines if discounts with multipliers out of `(0.0, 1.0]` are ignored instead of rejecting the order.
+///
+/// Read from `$IGNORE_OUT_OF_RANGE_DISCOUNTS`, defaults to `false`.
+fn out_of_range_discounts_ignored() -> bool {
+    env::var("IGNORE_OUT_OF_RANGE_DISCOUNTS")
+        .ok()
+        .and_then(|ignored| ignored.parse::<bool>().ok())
+        .unwrap_or(false)
+}
+
 /// Calculates the total sum of the undiscounted order items. Does not include shipping costs.
 ///
 /// This defines the semantic of the total amount that is passed to the Discount service, for figuring out which Discounts apply.
 /// Do not confuse with `calculate_compensatable_order_amount`, which is the total compensatable amount that the buyer needs to pay.
 ///
-/// Converts value to an `i64` as this is what the GraphQL client library expects.
+/// Sums up as `u64` to avoid overflows and converts the sum with `convert_to_graphql_int`.
 fn calculate_order_amount(
     pproduct_variant_versions_by_product_variant_ids: &HashMap<Uuid, ProductVariantVersion>,
-) -> i64 {
-    let order_amount: u32 = pproduct_variant_versions_by_product_variant_ids
-        .iter()
-        .map(|(_, p)| p.price)
+) -> Result<i64> {
+    let order_amount: u64 = pproduct_variant_versions_by_product_variant_ids
+        .values()
+        .map(|p| u64::from(p.price))
         .sum();
-    i64::from(order_amount)
+    convert_to_graphql_int(order_amount, "orderAmount")
+}
+
+/// Converts a value to an `i64` as the GraphQL client library expects, checking the bounds of a GraphQL `Int`.
+///
+/// The GraphQL client library represents `Int` as `i64`, but a GraphQL `Int` is a signed 32-bit integer.
+/// Values of the discount service's `orderAmount` and `count` and the shipment service's `quantity` must therefore be within `0..=i32::MAX`, larger values return an error instead of being rejected or truncated by the services.
+///
+/// * `value` - Value to convert.
+/// * `name` - Name of the GraphQL field of the value, used in the error message.
+fn convert_to_graphql_int(value: u64, name: &str) -> Result<i64> {
+    match i32::try_from(value) {
+        Ok(definitely_value) => Ok(i64::from(definitely_value)),
+        Err(_) => {
+            let message = format!(
+                "`{}` of value: `{}` exceeds the maximum of a GraphQL `Int` of `{}`.",
+                name,
+                value,
+                i32::MAX
+            );
+            Err(Error::new(message))
+        }
+    }
 }
 
 #[derive(GraphQLQuery)]
@@ -914,12 +2638,63 @@ async fn query_shipment_fees(
     order_item_inputs_by_product_variant_ids: &HashMap<Uuid, OrderItemInput>,
     product_variant_versions_by_product_variant_ids: &HashMap<Uuid, ProductVariantVersion>,
     counts_by_product_variant_ids: &HashMap<Uuid, u64>,
+    correlation_id: &CorrelationId,
 ) -> Result<u64> {
     let calculate_shipment_fees_input = build_calculate_shipment_fees_input(
         product_variant_versions_by_product_variant_ids,
         counts_by_product_variant_ids,
         order_item_inputs_by_product_variant_ids,
     )?;
+    send_shipment_fees_query(calculate_shipment_fees_input, correlation_id).await
+}
+
+/// Queries the total shipment fees of existing order items, e.g. after merging the order items of two orders.
+///
+/// Sends all order items in a single `calculateShipmentFees` query, like `query_shipment_fees`.
+///
+/// * `order_items` - Order items to query the shipment fees of.
+/// * `correlation_id` - Correlation id to forward to the shipment service.
+async fn query_order_items_shipment_fees(
+    order_items: &[OrderItem],
+    correlation_id: &CorrelationId,
+) -> Result<u64> {
+    let calculate_shipment_fees_input =
+        build_order_items_calculate_shipment_fees_input(order_items)?;
+    send_shipment_fees_query(calculate_shipment_fees_input, correlation_id).await
+}
+
+/// Builds the `get_shipment_fees::CalculateShipmentFeesInput` from the product variant versions, counts and shipment methods of order items.
+///
+/// * `order_items` - Order items to calculate the shipment fees of.
+fn build_order_items_calculate_shipment_fees_input(
+    order_items: &[OrderItem],
+) -> Result<get_shipment_fees::CalculateShipmentFeesInput> {
+    let items =
+        order_items
+            .iter()
+            .map(|order_item| {
+                Ok(
+                    get_shipment_fees::ProductVariantVersionWithQuantityAndShipmentMethodInput {
+                        product_variant_version_id: order_item.product_variant_version._id,
+                        quantity: convert_to_graphql_int(order_item.count, "quantity")?,
+                        shipment_method_id: order_item.shipment_method._id,
+                    },
+                )
+            })
+            .collect::<Result<
+                Vec<get_shipment_fees::ProductVariantVersionWithQuantityAndShipmentMethodInput>,
+            >>()?;
+    Ok(get_shipment_fees::CalculateShipmentFeesInput { items })
+}
+
+/// Sends the `calculateShipmentFees` query to the shipment service and returns the summed fees of its items.
+///
+/// * `calculate_shipment_fees_input` - Items to calculate the shipment fees of.
+/// * `correlation_id` - Correlation id to forward to the shipment service.
+async fn send_shipment_fees_query(
+    calculate_shipment_fees_input: get_shipment_fees::CalculateShipmentFeesInput,
+    correlation_id: &CorrelationId,
+) -> Result<u64> {
     let variables = get_shipment_fees::Variables {
         calculate_shipment_fees_input,
     };
@@ -928,11 +2703,13 @@ async fn query_shipment_fees(
     let client = reqwest::Client::new();
 
     let res = client
-        .post("http://localhost:3500/v1.0/invoke/shipment/method/graphql")
+        .post(dapr_url("v1.0/invoke/shipment/method/graphql"))
         .json(&request_body)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
         .send()
         .await?;
-    let response_body: Response<get_shipment_fees::ResponseData> = res.json().await?;
+    let response_body: Response<get_shipment_fees::ResponseData> =
+        deserialize_outbound_response("shipment", &request_body, res).await?;
     let message = "Response data of `query_shipment_fees` query is empty.";
     let response_data: get_shipment_fees::ResponseData =
         response_body.data.ok_or(Error::new(message))?;
@@ -961,7 +2738,7 @@ fn build_calculate_shipment_fees_input(
                 let product_variant_version_with_quantity_and_shipment_method_input =
                     get_shipment_fees::ProductVariantVersionWithQuantityAndShipmentMethodInput {
                         product_variant_version_id: product_variant_version._id,
-                        quantity: i64::try_from(*count)?,
+                        quantity: convert_to_graphql_int(*count, "quantity")?,
                         shipment_method_id,
                     };
                 Ok(product_variant_version_with_quantity_and_shipment_method_input)
@@ -973,17 +2750,171 @@ fn build_calculate_shipment_fees_input(
     Ok(calculate_shipment_fees_input)
 }
 
-/// Sends an `order/order/created` created event containing the order context.
-async fn send_order_created_event(order_dto: OrderDTO) -> Result<()> {
+/// Sends an order created event containing the order context.
+///
+/// The topic defaults to `order/order/created`.
+/// The payment authorization is only sent to Dapr, webhooks receive the order DTO without it.
+///
+/// * `event_config` - Configuration of the published order created event.
+/// * `order_dto` - Order DTO to send as event data.
+/// * `correlation_id` - Correlation id of the request which triggered the event.
+async fn send_order_created_event(
+    event_config: &EventConfig,
+    order_dto: OrderDTO,
+    correlation_id: &CorrelationId,
+) -> Result<()> {
     let client = reqwest::Client::new();
+    let topic = &event_config.order_created_topic;
     client
-        .post("http://localhost:3500/v1.0/publish/pubsub/order/order/created")
+        .post(event_config.publish_url(topic))
         .json(&order_dto)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
         .send()
-        .await?;
+        .await?
+        .error_for_status()?;
+    let webhook_order_dto = OrderDTO {
+        payment_authorization: None,
+        ..order_dto
+    };
+    dispatch_to_webhooks(topic, &webhook_order_dto);
+    Ok(())
+}
+
+/// Periodically republishes the order created events of placed orders, which are not marked as `event_published`.
+///
+/// Recovers from failed publications after the order placement was already saved in MongoDB, e.g. if the service crashed in between.
+/// Ensures at-least-once delivery of order created events, consumers must therefore deduplicate by order UUID.
+/// Only orders placed at least one interval ago are republished, so that publications in progress are not duplicated.
+/// Orders placed before `Order::event_published` existed do not store the attribute and are not republished.
+/// The interval is read from `$ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS` and defaults to `DEFAULT_ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS`, `0` disables the republication.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `event_config` - Configuration of the published order created event.
+pub async fn republish_unpublished_order_created_events(
+    collection: Collection<Order>,
+    event_config: EventConfig,
+) {
+    let republish_interval_seconds = env::var("ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS")
+        .ok()
+        .and_then(|interval| interval.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS);
+    if republish_interval_seconds == 0 {
+        info!("Republication of unpublished order created events is disabled.");
+        return;
+    }
+    let republish_interval = Duration::from_secs(republish_interval_seconds);
+    let mut interval = tokio::time::interval(republish_interval);
+    loop {
+        interval.tick().await;
+        let placed_before = DateTime::from(SystemTime::now() - republish_interval);
+        if let Err(error) =
+            republish_order_created_events(&collection, &event_config, placed_before).await
+        {
+            warn!(
+                "Republishing unpublished order created events failed: {}",
+                error.message
+            );
+        }
+    }
+}
+
+/// Republishes the order created events of all placed orders with unpublished events placed before a timestamp.
+///
+/// The payment authorization is not persisted and therefore not contained in the republished events.
+/// Failed republications are logged and retried in the next interval.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `event_config` - Configuration of the published order created event.
+/// * `placed_before` - Timestamp which the orders must be placed before.
+async fn republish_order_created_events(
+    collection: &Collection<Order>,
+    event_config: &EventConfig,
+    placed_before: DateTime,
+) -> Result<()> {
+    let filter = build_unpublished_order_created_events_filter(placed_before);
+    let unpublished_orders: Vec<Order> = match collection.find(filter, None).await {
+        Ok(cursor) => cursor.try_collect().await?,
+        Err(_) => {
+            return Err(Error::new(
+                "Placed orders with unpublished order created events could not be retrieved.",
+            ))
+        }
+    };
+    for order in unpublished_orders {
+        let id = order._id;
+        match republish_order_created_event(collection, event_config, order).await {
+            Ok(_) => info!(
+                "Republished order created event of order of UUID: `{}`.",
+                id
+            ),
+            Err(error) => warn!(
+                "Republishing order created event of order of UUID: `{}` failed: {}",
+                id, error.message
+            ),
+        }
+    }
     Ok(())
 }
 
+/// Builds the MongoDB filter of placed orders with unpublished order created events placed before a timestamp.
+///
+/// Orders without the `event_published` attribute do not match.
+///
+/// * `placed_before` - Timestamp which the orders must be placed before.
+fn build_unpublished_order_created_events_filter(placed_before: DateTime) -> Document {
+    doc! {
+        "order_status": OrderStatus::Placed,
+        "event_published": false,
+        "placed_at": {"$lt": placed_before},
+    }
+}
+
+/// Sends the order created event of a placed order with a new correlation id and marks the order as `event_published` afterwards.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `event_config` - Configuration of the published order created event.
+/// * `order` - Placed order to republish the order created event of.
+async fn republish_order_created_event(
+    collection: &Collection<Order>,
+    event_config: &EventConfig,
+    order: Order,
+) -> Result<()> {
+    let id = order._id;
+    let order_dto = OrderDTO::try_from((order, None))?;
+    let correlation_id = CorrelationId(Uuid::new().to_string());
+    send_order_created_event(event_config, order_dto, &correlation_id).await?;
+    set_event_published_in_mongodb(collection, id).await
+}
+
+/// Checks if the addresses of an order are still registered under the user when placing the order.
+///
+/// Addresses archived since order creation are no longer registered under the user, the order can then not be placed.
+/// The shipment address of gift orders does not need to be registered under the user.
+///
+/// * `db_client` - MongoDB database client containing the users.
+/// * `order` - Order to check addresses of.
+async fn validate_addresses_at_placement(db_client: &Database, order: &Order) -> Result<()> {
+    let user_collection: mongodb::Collection<User> = db_client.collection::<User>(USERS);
+    let user = query_object(&user_collection, order.user._id).await?;
+    let mut addresses = vec![("Invoice", order.invoice_address._id)];
+    if !order.is_gift {
+        addresses.push(("Shipment", order.shipment_address._id));
+    }
+    match addresses
+        .iter()
+        .find(|(_, address_id)| !user.user_address_ids.contains(address_id))
+    {
+        Some((address_kind, address_id)) => {
+            let message = format!(
+                "{} address with UUID: `{}` of order with UUID: `{}` is no longer registered under user with UUID: `{}`, it was archived since order creation. Order can not be placed.",
+                address_kind, address_id, order._id, order.user._id
+            );
+            Err(Error::new(message))
+        }
+        None => Ok(()),
+    }
+}
+
 /// Checks if an address is registered under a specific user (MongoDB database populated with events).
 ///
 /// Used before creating orders.
@@ -992,7 +2923,10 @@ async fn validate_user_address(
     id: Uuid,
     user_id: Uuid,
 ) -> Result<()> {
-    match collection.find_one(doc! {"_id": user_id }, None).await {
+    match collection
+        .find_one(doc! {"_id": user_id, "user_address_ids": id }, None)
+        .await
+    {
         Ok(maybe_object) => match maybe_object {
             Some(_) => Ok(()),
             None => {
@@ -1013,6 +2947,33 @@ async fn validate_user_address(
     }
 }
 
+/// Checks if an address is registered under any user, e.g. the recipient of a gift order (MongoDB database populated with events).
+///
+/// * `collection` - MongoDB collection of users.
+/// * `id` - UUID of the address.
+async fn validate_registered_address(collection: &Collection<User>, id: Uuid) -> Result<()> {
+    match collection
+        .find_one(doc! {"user_address_ids": id }, None)
+        .await
+    {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => {
+            let message = format!(
+                "Shipment address with UUID: `{}` of gift order is not registered under any user.",
+                id
+            );
+            Err(Error::new(message))
+        }
+        Err(_) => {
+            let message = format!(
+                "Checking registration of shipment address with UUID: `{}` failed in MongoDB.",
+                id
+            );
+            Err(Error::new(message))
+        }
+    }
+}
+
 /// Checks if a single object is in the system (MongoDB database populated with events).
 ///
 /// Used before creating orders.
@@ -1025,6 +2986,7 @@ pub async fn validate_object<T: for<'a> Deserialize<'a> + Unpin + Send + Sync>(
 
 /// Checks if all objects are in the system (MongoDB database populated with events).
 ///
+/// Returns a single error listing all UUIDs of objects which are not present.
 /// Used before creating orders.
 async fn validate_objects<T: for<'b> Deserialize<'b> + Unpin + Send + Sync + PartialEq + Clone>(
     collection: &Collection<T>,
@@ -1043,24 +3005,24 @@ where
                 .iter()
                 .map(|object: &T| Uuid::from(object.clone()))
                 .collect();
-            object_ids
-                .iter()
-                .fold(Ok(()), |o, id| match ids.contains(id) {
-                    true => o.and(Ok(())),
-                    false => {
-                        let message = format!(
-                            "{} with UUID: `{}` is not present in the system.",
-                            type_name::<T>(),
-                            id
-                        );
-                        Err(Error::new(message))
-                    }
-                })
+            let missing_ids: Vec<&Uuid> =
+                object_ids.iter().filter(|id| !ids.contains(id)).collect();
+            match missing_ids.len() {
+                0 => Ok(()),
+                _ => {
+                    let message = format!(
+                        "{} with UUIDs: `{:?}` are not present in the system.",
+                        domain_type_name::<T>(),
+                        missing_ids
+                    );
+                    Err(Error::new(message))
+                }
+            }
         }
         Err(_) => {
             let message = format!(
                 "{} with specified UUIDs are not present in the system.",
-                type_name::<T>()
+                domain_type_name::<T>()
             );
             Err(Error::new(message))
         }
@@ -1079,3 +3041,324 @@ fn build_hash_map_error<V>(_hash_map: &HashMap<Uuid, V>, id: Uuid) -> Error {
     );
     Error::new(message)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures;
+
+    /// Builds an order item of a product variant with a price of `1000`.
+    fn build_order_item(product_variant_id: Uuid, count: u64) -> OrderItem {
+        test_fixtures::build_order_item(product_variant_id, 1000, count)
+    }
+
+    #[test]
+    fn unpublished_order_created_events_filter_matches_only_placed_orders_with_unpublished_events()
+    {
+        let placed_before = DateTime::from_millis(1_000);
+        let filter = build_unpublished_order_created_events_filter(placed_before);
+        assert_eq!(
+            filter.get("order_status"),
+            Some(&Bson::from(OrderStatus::Placed))
+        );
+        assert_eq!(filter.get_bool("event_published"), Ok(false));
+        assert_eq!(
+            filter
+                .get_document("placed_at")
+                .unwrap()
+                .get_datetime("$lt"),
+            Ok(&placed_before)
+        );
+    }
+
+    #[test]
+    fn gift_recipient_address_is_only_allowed_for_gift_orders() {
+        let input = GiftRecipientAddressInput {
+            name: "Erika Mustermann".to_string(),
+            street: "Heidestraße 17".to_string(),
+            postal_code: "51147".to_string(),
+            city: "Köln".to_string(),
+            country: "DE".to_string(),
+        };
+        assert!(validate_gift_recipient_address(false, Some(input.clone())).is_err());
+        assert!(validate_gift_recipient_address(true, Some(input))
+            .unwrap()
+            .is_some());
+        assert!(validate_gift_recipient_address(false, None)
+            .unwrap()
+            .is_none());
+    }
+
+    /// Builds an order item input of a shopping cart item.
+    fn build_order_item_input(shopping_cart_item_id: Uuid) -> OrderItemInput {
+        OrderItemInput {
+            shopping_cart_item_id,
+            shipment_method_id: Uuid::new(),
+            coupon_ids: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn shopping_cart_items_of_the_same_product_variant_are_rejected() {
+        let product_variant_id = Uuid::new();
+        let shopping_cart_item_ids = [Uuid::new(), Uuid::new(), Uuid::new()];
+        let order_item_inputs: BTreeSet<OrderItemInput> = shopping_cart_item_ids
+            .iter()
+            .map(|id| build_order_item_input(*id))
+            .collect();
+        let mut ids_and_counts = HashMap::from([
+            (shopping_cart_item_ids[0], (product_variant_id, 1)),
+            (shopping_cart_item_ids[1], (Uuid::new(), 1)),
+            (shopping_cart_item_ids[2], (Uuid::new(), 2)),
+        ]);
+        assert!(verify_product_variants_unique(&order_item_inputs, &ids_and_counts).is_ok());
+        ids_and_counts.insert(shopping_cart_item_ids[2], (product_variant_id, 2));
+        assert!(verify_product_variants_unique(&order_item_inputs, &ids_and_counts).is_err());
+    }
+
+    #[test]
+    fn empty_shopping_cart_response_is_an_error() {
+        let user_id = Uuid::new();
+        for entities in [serde_json::json!([]), serde_json::json!([null])] {
+            let response_data: get_shopping_cart_product_variant_ids_and_counts::ResponseData =
+                serde_json::from_value(serde_json::json!({ "_entities": entities })).unwrap();
+            assert!(extract_shopping_cart_entity(response_data, user_id).is_err());
+        }
+    }
+
+    #[test]
+    fn shopping_cart_items_are_extracted_from_shopping_cart_response() {
+        let shopping_cart_item_id = Uuid::new();
+        let product_variant_id = Uuid::new();
+        let response_data: get_shopping_cart_product_variant_ids_and_counts::ResponseData =
+            serde_json::from_value(serde_json::json!({
+                "_entities": [{
+                    "__typename": "User",
+                    "shoppingcart": {
+                        "shoppingcartItems": {
+                            "nodes": [{
+                                "id": shopping_cart_item_id,
+                                "productVariant": { "id": product_variant_id },
+                                "count": 3
+                            }]
+                        }
+                    }
+                }]
+            }))
+            .unwrap();
+        let entity = extract_shopping_cart_entity(response_data, Uuid::new()).unwrap();
+        assert_eq!(
+            into_ids_and_counts_by_shopping_cart_item_ids(entity).unwrap(),
+            HashMap::from([(shopping_cart_item_id, (product_variant_id, 3))])
+        );
+    }
+
+    #[test]
+    fn product_variants_omitted_by_discount_service_have_no_discounts() {
+        let discounted_product_variant_id = Uuid::new();
+        let omitted_product_variant_id = Uuid::new();
+        let discount_id = Uuid::new();
+        let response_data = vec![get_discounts::GetDiscountsFindApplicableDiscounts {
+            product_variant_id: discounted_product_variant_id,
+            discounts: vec![
+                get_discounts::GetDiscountsFindApplicableDiscountsDiscounts {
+                    id: discount_id,
+                    discount: 0.1,
+                },
+            ],
+        }];
+        let discounts_by_product_variant_ids = remap_discounts_to_product_variants(
+            response_data,
+            &vec![discounted_product_variant_id, omitted_product_variant_id],
+        )
+        .unwrap();
+        assert_eq!(discounts_by_product_variant_ids.len(), 2);
+        let discounts = &discounts_by_product_variant_ids[&discounted_product_variant_id].discounts;
+        assert_eq!(discounts.len(), 1);
+        assert_eq!(discounts[0].id, discount_id);
+        let omitted_discounts = &discounts_by_product_variant_ids[&omitted_product_variant_id];
+        assert_eq!(
+            omitted_discounts.product_variant_id,
+            omitted_product_variant_id
+        );
+        assert!(omitted_discounts.discounts.is_empty());
+    }
+
+    #[test]
+    fn placement_only_matches_pending_orders() {
+        let id = Uuid::new();
+        let filter = build_pending_order_filter(id);
+        assert_eq!(filter.get("_id"), Some(&Bson::from(id)));
+        assert_eq!(
+            filter.get("order_status"),
+            Some(&Bson::from(OrderStatus::Pending))
+        );
+    }
+
+    #[test]
+    fn order_item_count_update_only_matches_order_items_of_pending_orders() {
+        let order_id = Uuid::new();
+        let order_item_id = Uuid::new();
+        let filter = build_pending_order_item_filter(order_id, order_item_id);
+        assert_eq!(filter.get("_id"), Some(&Bson::from(order_id)));
+        assert_eq!(
+            filter.get("order_status"),
+            Some(&Bson::from(OrderStatus::Pending))
+        );
+        assert_eq!(
+            filter.get("internal_order_items._id"),
+            Some(&Bson::from(order_item_id))
+        );
+    }
+
+    #[test]
+    fn discount_multipliers_out_of_range_are_rejected_or_ignored() {
+        let product_variant_id = Uuid::new();
+        let build_discount = |discount: f64| Discount {
+            _id: Uuid::new(),
+            discount,
+        };
+        for multiplier in [0.5, 1.0, f64::MIN_POSITIVE] {
+            let discount = build_discount(multiplier);
+            assert_eq!(
+                validate_discount_multiplier(discount, product_variant_id, false)
+                    .unwrap()
+                    .map(|discount| discount.discount),
+                Some(multiplier)
+            );
+        }
+        for multiplier in [0.0, -0.5, 1.01, f64::NAN] {
+            assert!(validate_discount_multiplier(
+                build_discount(multiplier),
+                product_variant_id,
+                false
+            )
+            .is_err());
+            assert!(validate_discount_multiplier(
+                build_discount(multiplier),
+                product_variant_id,
+                true
+            )
+            .unwrap()
+            .is_none());
+        }
+    }
+
+    #[test]
+    fn values_exceeding_a_graphql_int_are_rejected() {
+        assert_eq!(convert_to_graphql_int(0, "count").unwrap(), 0);
+        assert_eq!(
+            convert_to_graphql_int(i32::MAX as u64, "count").unwrap(),
+            i64::from(i32::MAX)
+        );
+        assert!(convert_to_graphql_int(i32::MAX as u64 + 1, "count").is_err());
+        assert!(convert_to_graphql_int(u64::MAX, "quantity").is_err());
+    }
+
+    #[test]
+    fn order_amount_is_summed_without_overflow() {
+        let build_product_variant_version = |price: u32| ProductVariantVersion {
+            _id: Uuid::new(),
+            price,
+            tax_rate_id: Uuid::new(),
+        };
+        let product_variant_versions_by_product_variant_ids = HashMap::from([
+            (Uuid::new(), build_product_variant_version(1000)),
+            (Uuid::new(), build_product_variant_version(2500)),
+        ]);
+        assert_eq!(
+            calculate_order_amount(&product_variant_versions_by_product_variant_ids).unwrap(),
+            3500
+        );
+        let product_variant_versions_by_product_variant_ids = HashMap::from([
+            (Uuid::new(), build_product_variant_version(u32::MAX)),
+            (Uuid::new(), build_product_variant_version(u32::MAX)),
+        ]);
+        assert!(calculate_order_amount(&product_variant_versions_by_product_variant_ids).is_err());
+    }
+
+    #[test]
+    fn vat_numbers_are_normalized_and_prefixed_with_store_country() {
+        assert_eq!(
+            validate_vat_number(Some(" de 123 456 789 ".to_string()), "DE").unwrap(),
+            Some("DE123456789".to_string())
+        );
+        assert_eq!(
+            validate_vat_number(Some("123456789".to_string()), "at").unwrap(),
+            Some("AT123456789".to_string())
+        );
+        assert_eq!(validate_vat_number(None, "DE").unwrap(), None);
+    }
+
+    #[test]
+    fn vat_numbers_with_invalid_format_are_rejected() {
+        assert!(validate_vat_number(Some("DE1".to_string()), "DE").is_err());
+        assert!(validate_vat_number(Some("DE1234567890123".to_string()), "DE").is_err());
+        assert!(validate_vat_number(Some("DE123-456".to_string()), "DE").is_err());
+        assert!(validate_vat_number(Some("Ä123456789".to_string()), "DE").is_err());
+        assert!(validate_vat_number(Some("DEÄ23456789".to_string()), "DE").is_err());
+        assert!(validate_vat_number(Some("123456789".to_string()), "Ä").is_err());
+    }
+
+    #[test]
+    fn item_quantity_cap_allows_counts_up_to_maximum() {
+        let counts_by_product_variant_ids = HashMap::from([(Uuid::new(), 5), (Uuid::new(), 1)]);
+        assert!(verify_item_quantity_cap_of(&counts_by_product_variant_ids, Some(5)).is_ok());
+        assert!(verify_item_quantity_cap_of(&counts_by_product_variant_ids, None).is_ok());
+    }
+
+    #[test]
+    fn item_quantity_cap_rejects_counts_above_maximum() {
+        let counts_by_product_variant_ids = HashMap::from([(Uuid::new(), 6), (Uuid::new(), 1)]);
+        assert!(verify_item_quantity_cap_of(&counts_by_product_variant_ids, Some(5)).is_err());
+    }
+
+    #[test]
+    fn merged_order_items_sum_counts_and_recalculate_compensatable_amounts() {
+        let product_variant_id = Uuid::new();
+        let mut order_items = vec![build_order_item(product_variant_id, 3)];
+        let order_items_to_merge = vec![
+            build_order_item(product_variant_id, 4),
+            build_order_item(Uuid::new(), 1),
+        ];
+        merge_order_items(
+            &mut order_items,
+            order_items_to_merge,
+            RoundingStrategy::HalfUp,
+        );
+        assert_eq!(order_items.len(), 2);
+        assert_eq!(order_items[0].count, 7);
+        assert_eq!(order_items[0].compensatable_amount, 7000);
+        let counts_by_product_variant_ids: HashMap<Uuid, u64> = order_items
+            .iter()
+            .map(|order_item| (order_item.product_variant._id, order_item.count))
+            .collect();
+        assert!(verify_item_quantity_cap_of(&counts_by_product_variant_ids, Some(5)).is_err());
+    }
+
+    #[test]
+    fn shipment_fees_input_of_merged_order_items_contains_each_order_item_once() {
+        let product_variant_id = Uuid::new();
+        let mut order_items = vec![build_order_item(product_variant_id, 3)];
+        merge_order_items(
+            &mut order_items,
+            vec![
+                build_order_item(product_variant_id, 2),
+                build_order_item(Uuid::new(), 1),
+            ],
+            RoundingStrategy::HalfUp,
+        );
+        let calculate_shipment_fees_input =
+            build_order_items_calculate_shipment_fees_input(&order_items).unwrap();
+        let quantities: Vec<i64> = calculate_shipment_fees_input
+            .items
+            .iter()
+            .map(|item| item.quantity)
+            .collect();
+        assert_eq!(quantities, vec![5, 1]);
+        assert_eq!(
+            calculate_shipment_fees_input.items[0].shipment_method_id,
+            order_items[0].shipment_method._id
+        );
+    }
+}
@@ -3,6 +3,7 @@ use bson::Uuid;
 use std::{
     cmp::Ordering,
     collections::{BTreeSet, HashSet},
+    fmt,
 };
 
 #[derive(Debug, InputObject)]
@@ -19,6 +20,30 @@ pub struct CreateOrderInput {
     pub payment_information_id: Uuid,
     /// Optional VAT number.
     pub vat_number: Option<String>,
+    /// Whether the order is a gift, which allows a shipment address that is not registered under the user.
+    #[graphql(default)]
+    pub is_gift: bool,
+    /// Optional free-form address of the gift recipient, which gift orders are shipped to instead of a registered address.
+    ///
+    /// Can only be set for gift orders.
+    pub gift_recipient_address: Option<GiftRecipientAddressInput>,
+    /// Optional note of the customer, e.g. delivery instructions. Limited to 500 characters.
+    pub customer_note: Option<String>,
+}
+
+/// Free-form address of the recipient of a gift order.
+#[derive(Debug, InputObject, Clone)]
+pub struct GiftRecipientAddressInput {
+    /// Name of the gift recipient.
+    pub name: String,
+    /// Street and house number.
+    pub street: String,
+    /// Postal code.
+    pub postal_code: String,
+    /// City.
+    pub city: String,
+    /// ISO 3166-1 alpha-2 code of the country.
+    pub country: String,
 }
 
 #[derive(Debug, InputObject, PartialEq, Eq, Clone)]
@@ -31,12 +56,21 @@ pub struct OrderItemInput {
     pub coupon_ids: HashSet<Uuid>,
 }
 
-#[derive(Debug, InputObject, Clone)]
+#[derive(InputObject, Clone)]
 pub struct PaymentAuthorizationInput {
     /// CVC/CVV number of 3-4 digits.
     pub cvc: Option<u16>,
 }
 
+/// Redacts the CVC, so that it can not be logged.
+impl fmt::Debug for PaymentAuthorizationInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PaymentAuthorizationInput")
+            .field("cvc", &self.cvc.map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
 impl PartialOrd for OrderItemInput {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         self.shopping_cart_item_id
@@ -57,3 +91,49 @@ pub struct PlaceOrderInput {
     /// Optional payment authorization data.
     pub payment_authorization: Option<PaymentAuthorizationInput>,
 }
+
+#[derive(Debug, InputObject)]
+pub struct CreateAndPlaceOrderInput {
+    /// Order to create.
+    pub order: CreateOrderInput,
+    /// Optional payment authorization data.
+    pub payment_authorization: Option<PaymentAuthorizationInput>,
+}
+
+#[derive(Debug, InputObject)]
+pub struct RefundOrderItemsInput {
+    /// UUID of order to refund order items of.
+    pub order_id: Uuid,
+    /// UUIDs of order items to refund.
+    pub order_item_ids: HashSet<Uuid>,
+    /// Reason of refund.
+    pub reason: String,
+}
+
+#[derive(Debug, InputObject)]
+pub struct UpdateOrderItemCountInput {
+    /// UUID of order containing the order item.
+    pub order_id: Uuid,
+    /// UUID of order item to update.
+    pub order_item_id: Uuid,
+    /// New quantity of the order item, must be greater than zero.
+    pub count: u64,
+}
+
+#[derive(Debug, InputObject)]
+pub struct UpdateOrderPaymentInformationInput {
+    /// UUID of order to update the payment information of.
+    pub order_id: Uuid,
+    /// UUID of payment information that the order should be processed with.
+    pub payment_information_id: Uuid,
+}
+
+#[derive(Debug, InputObject)]
+pub struct ApplyCouponToOrderItemInput {
+    /// UUID of order containing the order item.
+    pub order_id: Uuid,
+    /// UUID of order item to apply the coupon to.
+    pub order_item_id: Uuid,
+    /// UUID of coupon to apply.
+    pub coupon_id: Uuid,
+}
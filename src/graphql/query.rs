@@ -1,15 +1,50 @@
-use std::{any::type_name, collections::HashMap};
+use std::{
+    any::type_name,
+    collections::HashMap,
+    time::{Duration, SystemTime},
+};
 
 use async_graphql::{Context, Error, Object, Result};
 
-use bson::Uuid;
+use bson::{DateTime, Document, Uuid};
 use futures::TryStreamExt;
-use mongodb::{bson::doc, Collection, Database};
+use mongodb::{
+    bson::doc,
+    options::{FindOneOptions, FindOptions},
+    Collection, Database,
+};
+use mongodb_cursor_pagination::{error::CursorError, FindResult, PaginatedCursor};
 use serde::Deserialize;
+use tracing::info;
 
-use crate::authorization::authorize_user;
+use crate::{
+    authorization::{authorize_admin, authorize_user},
+    collection_names::{ORDERS, ORDER_COMPENSATIONS, ORDER_ITEMS, PRODUCT_VARIANT_VERSIONS, USERS},
+    event::order_compensation::OrderCompensation,
+    rounding_strategy::RoundingStrategy,
+    store_config::StoreConfig,
+};
 
-use super::model::{order::Order, order_item::OrderItem, user::User};
+use super::{
+    model::{
+        connection::{
+            base_connection::{clamp_page_size, BaseConnection, FindResultWrapper},
+            order_connection::OrderConnection,
+        },
+        foreign_types::ProductVariantVersion,
+        iso_8601_duration::Iso8601Duration,
+        order::{Order, OrderStatus},
+        order_datatypes::OrderOrderInput,
+        order_integrity::OrderIntegrityReport,
+        order_item::OrderItem,
+        order_preview::OrderPreview,
+        order_summary::OrderSummary,
+        retained_product_variant_version::RetainedProductVariantVersion,
+        user::User,
+    },
+    mutation::{build_order, PENDING_TIMEOUT},
+    mutation_input_structs::CreateOrderInput,
+};
 
 /// Describes GraphQL order queries.
 pub struct Query;
@@ -24,7 +59,7 @@ impl Query {
         #[graphql(desc = "UUID of user to retrieve.")] id: Uuid,
     ) -> Result<User> {
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<User> = db_client.collection::<User>("users");
+        let collection: Collection<User> = db_client.collection::<User>(USERS);
         query_object(&collection, id).await
     }
 
@@ -35,13 +70,193 @@ impl Query {
         #[graphql(desc = "UUID of order to retrieve.")] id: Uuid,
     ) -> Result<Order> {
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<Order> = db_client.collection::<Order>("orders");
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
         let order = query_object(&collection, id).await?;
         authorize_user(&ctx, Some(order.user._id))?;
         Ok(order)
     }
 
+    /// Previews the totals of an order without creating it.
+    ///
+    /// Runs the same validation, availability check and discount, shipment fee and tax calculations as `create_order`, nothing is persisted.
+    async fn preview_order<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "CreateOrderInput")] input: CreateOrderInput,
+    ) -> Result<OrderPreview> {
+        let order = build_order(ctx, input).await?;
+        let store_config = ctx.data::<StoreConfig>()?;
+        OrderPreview::new(order, &store_config.currency)
+    }
+
+    /// Retrieves a product variant version of specific UUID, including versions which are no longer current.
+    ///
+    /// Historical order items can therefore resolve their exact product variant version, e.g. for invoices.
+    /// Only product variant versions received while `$PRODUCT_VARIANT_VERSIONS_RETAINED` is enabled can be retrieved.
+    /// Returns the price and tax rate of the version, which the `ProductVariantVersion` foreign type does not expose.
+    async fn product_variant_version<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of product variant version to retrieve.")] id: Uuid,
+    ) -> Result<RetainedProductVariantVersion> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<ProductVariantVersion> =
+            db_client.collection::<ProductVariantVersion>(PRODUCT_VARIANT_VERSIONS);
+        let product_variant_version = query_object(&collection, id).await?;
+        let store_config = ctx.data::<StoreConfig>()?;
+        RetainedProductVariantVersion::new(&product_variant_version, &store_config.currency)
+    }
+
+    /// Retrieves the summary of an order of specific UUID.
+    ///
+    /// Does not require authorization, as the summary only contains fields which are safe to share.
+    async fn public_order_summary<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of order to retrieve summary of.")] id: Uuid,
+    ) -> Result<OrderSummary> {
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = query_object(&collection, id).await?;
+        let store_config = ctx.data::<StoreConfig>()?;
+        OrderSummary::new(&order, &store_config.currency)
+    }
+
+    /// Verifies the integrity of an order of specific UUID for financial auditing.
+    ///
+    /// Recomputes the amounts of the order from its stored order items and compares them to the persisted amounts.
+    /// Only accessible to admins.
+    async fn verify_order_integrity<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of order to verify integrity of.")] order_id: Uuid,
+    ) -> Result<OrderIntegrityReport> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order = query_object(&collection, order_id).await?;
+        let store_config = ctx.data::<StoreConfig>()?;
+        let rounding_strategy = ctx.data::<RoundingStrategy>()?;
+        let order_integrity_report =
+            OrderIntegrityReport::new(&order, &store_config.currency, *rounding_strategy)?;
+        if !order_integrity_report.is_consistent {
+            info!("Order of UUID: `{}` is inconsistent.", order_id);
+        }
+        Ok(order_integrity_report)
+    }
+
+    /// Retrieves the current `OrderStatus::Pending` order of a user, `None` if the user has no pending order.
+    ///
+    /// Returns the most recently created pending order if the user has multiple pending orders.
+    async fn current_pending_order<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of user to retrieve current pending order of.")] user_id: Uuid,
+    ) -> Result<Option<Order>> {
+        authorize_user(ctx, Some(user_id))?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        query_current_pending_order(&collection, user_id).await
+    }
+
+    /// Searches orders by a prefix of the UUID of the order, which serves as order number, its user or one of its addresses.
+    ///
+    /// The prefix consists of hexadecimal digits and may contain hyphens, a complete UUID matches exactly.
+    /// Only accessible to admins, used by support staff.
+    async fn search_orders<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(
+            desc = "Prefix of the UUID of the order, the user or an address of the orders to search for."
+        )]
+        query: String,
+        #[graphql(
+            desc = "Describes that the `first` N orders should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
+        first: Option<u32>,
+        #[graphql(desc = "Describes how many orders should be skipped at the beginning.")]
+        skip: Option<u64>,
+        #[graphql(desc = "Specifies the order in which orders are retrieved.")] order_by: Option<
+            OrderOrderInput,
+        >,
+    ) -> Result<OrderConnection> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let (lowest_id, highest_id) = build_uuid_prefix_range(&query)?;
+        let id_range = doc! {"$gte": lowest_id, "$lte": highest_id};
+        let filter = doc! {"$or": [
+            {"_id": id_range.clone()},
+            {"user._id": id_range.clone()},
+            {"shipment_address._id": id_range.clone()},
+            {"invoice_address._id": id_range},
+        ]};
+        query_order_connection(&collection, filter, first, skip, order_by).await
+    }
+
+    /// Retrieves all orders containing a specific product variant.
+    ///
+    /// Only accessible to admins, used for recalls and sales analysis.
+    async fn orders_with_product_variant<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of the product variant which the orders should contain.")]
+        product_variant_id: Uuid,
+        #[graphql(
+            desc = "Describes that the `first` N orders should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
+        first: Option<u32>,
+        #[graphql(desc = "Describes how many orders should be skipped at the beginning.")]
+        skip: Option<u64>,
+        #[graphql(desc = "Specifies the order in which orders are retrieved.")] order_by: Option<
+            OrderOrderInput,
+        >,
+    ) -> Result<OrderConnection> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let filter = doc! {"internal_order_items.product_variant._id": product_variant_id};
+        query_order_connection(&collection, filter, first, skip, order_by).await
+    }
+
+    /// Retrieves all orders which are `OrderStatus::Pending` for longer than the pending timeout.
+    ///
+    /// Only accessible to admins, used to spot abandoned or stuck orders.
+    async fn stale_pending_orders<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(
+            desc = "ISO 8601 duration which the orders must be pending for at least, defaults to the pending timeout."
+        )]
+        pending_longer_than: Option<Iso8601Duration>,
+        #[graphql(
+            desc = "Describes that the `first` N orders should be retrieved, clamped to the maximum page size of 100 by default."
+        )]
+        first: Option<u32>,
+        #[graphql(desc = "Describes how many orders should be skipped at the beginning.")]
+        skip: Option<u64>,
+        #[graphql(desc = "Specifies the order in which orders are retrieved.")] order_by: Option<
+            OrderOrderInput,
+        >,
+    ) -> Result<OrderConnection> {
+        authorize_admin(ctx)?;
+        let db_client = ctx.data::<Database>()?;
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let pending_duration = pending_longer_than
+            .map(Duration::from)
+            .unwrap_or(PENDING_TIMEOUT);
+        let pending_timeout_bound = DateTime::from_system_time(
+            SystemTime::now()
+                .checked_sub(pending_duration)
+                .unwrap_or(SystemTime::UNIX_EPOCH),
+        );
+        let filter = doc! {"order_status": OrderStatus::Pending, "created_at": {"$lt": pending_timeout_bound}};
+        query_order_connection(&collection, filter, first, skip, order_by).await
+    }
+
     /// Entity resolver for order of specific UUID.
+    ///
+    /// Authorizes the user the same way as the `order` query, as the entity resolver is reachable through the federation gateway.
     #[graphql(entity)]
     async fn order_entity_resolver<'a>(
         &self,
@@ -49,8 +264,9 @@ impl Query {
         #[graphql(key, desc = "UUID of order to retrieve.")] id: Uuid,
     ) -> Result<Order> {
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<Order> = db_client.collection::<Order>("orders");
+        let collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
         let order = query_object(&collection, id).await?;
+        authorize_user(ctx, Some(order.user._id))?;
         Ok(order)
     }
 
@@ -61,16 +277,33 @@ impl Query {
         #[graphql(desc = "UUID of order_item to retrieve.")] id: Uuid,
     ) -> Result<OrderItem> {
         let db_client = ctx.data::<Database>()?;
-        let order_collection: Collection<Order> = db_client.collection::<Order>("orders");
+        let order_collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
         let order_item_collection: Collection<OrderItem> =
-            db_client.collection::<OrderItem>("order_items");
+            db_client.collection::<OrderItem>(ORDER_ITEMS);
         let order_item = query_object(&order_item_collection, id).await?;
         let user = query_user_from_order_item_id(&order_collection, id).await?;
         authorize_user(&ctx, Some(user._id))?;
         Ok(order_item)
     }
 
+    /// Retrieves order compensations of an order of specific UUID.
+    async fn order_compensations<'a>(
+        &self,
+        ctx: &Context<'a>,
+        #[graphql(desc = "UUID of order to retrieve order compensations of.")] order_id: Uuid,
+    ) -> Result<Vec<OrderCompensation>> {
+        let db_client = ctx.data::<Database>()?;
+        let order_collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let order_compensation_collection: Collection<OrderCompensation> =
+            db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS);
+        let order = query_object(&order_collection, order_id).await?;
+        authorize_user(ctx, Some(order.user._id))?;
+        query_order_compensations_of_order(&order_compensation_collection, order_id).await
+    }
+
     /// Entity resolver for order_item of specific UUID.
+    ///
+    /// Authorizes the user the same way as the `order_item` query, as the entity resolver is reachable through the federation gateway.
     #[graphql(entity)]
     async fn order_item_entity_resolver<'a>(
         &self,
@@ -78,8 +311,11 @@ impl Query {
         #[graphql(key, desc = "UUID of order_item to retrieve.")] id: Uuid,
     ) -> Result<OrderItem> {
         let db_client = ctx.data::<Database>()?;
-        let collection: Collection<OrderItem> = db_client.collection::<OrderItem>("order_items");
+        let order_collection: Collection<Order> = db_client.collection::<Order>(ORDERS);
+        let collection: Collection<OrderItem> = db_client.collection::<OrderItem>(ORDER_ITEMS);
         let order_item = query_object(&collection, id).await?;
+        let user = query_user_from_order_item_id(&order_collection, id).await?;
+        authorize_user(ctx, Some(user._id))?;
         Ok(order_item)
     }
 }
@@ -89,24 +325,152 @@ impl Query {
 /// * `collection` - MongoDB collection of orders to retrieve user of order item from.
 /// * `id` - UUID of order item.
 async fn query_user_from_order_item_id(collection: &Collection<Order>, id: Uuid) -> Result<User> {
-    match collection
+    let order = query_order_from_order_item_id(collection, id).await?;
+    Ok(order.user)
+}
+
+/// Queries the order containing an order item of specific UUID.
+///
+/// * `collection` - MongoDB collection of orders to retrieve order of order item from.
+/// * `id` - UUID of order item.
+pub async fn query_order_from_order_item_id(
+    collection: &Collection<Order>,
+    id: Uuid,
+) -> Result<Order> {
+    query_optional_order_from_order_item_id(collection, id)
+        .await?
+        .ok_or_else(|| {
+            let message = format!("OrderItem with UUID: `{}` not found.", id);
+            Error::new(message)
+        })
+}
+
+/// Queries the order containing an order item of specific UUID, `None` if no persisted order contains the order item.
+///
+/// Order items of order previews are never persisted.
+///
+/// * `collection` - MongoDB collection of orders to retrieve order of order item from.
+/// * `id` - UUID of order item.
+pub async fn query_optional_order_from_order_item_id(
+    collection: &Collection<Order>,
+    id: Uuid,
+) -> Result<Option<Order>> {
+    collection
         .find_one(doc! {"internal_order_items._id": id }, None)
         .await
-    {
-        Ok(maybe_order) => match maybe_order {
-            Some(order) => Ok(order.user),
-            None => {
-                let message = format!("OrderItem with UUID: `{}` not found.", id);
-                Err(Error::new(message))
-            }
-        },
+        .map_err(|_| {
+            let message = format!("Querying order of OrderItem with UUID: `{}` failed.", id);
+            Error::new(message)
+        })
+}
+
+/// Queries all order compensations of an order.
+///
+/// * `collection` - MongoDB collection of order compensations.
+/// * `order_id` - UUID of order to query order compensations of.
+pub async fn query_order_compensations_of_order(
+    collection: &Collection<OrderCompensation>,
+    order_id: Uuid,
+) -> Result<Vec<OrderCompensation>> {
+    let message = format!(
+        "Order compensations of order with UUID: `{}` could not be retrieved.",
+        order_id
+    );
+    match collection.find(doc! {"order_id": order_id }, None).await {
+        Ok(cursor) => cursor.try_collect().await.map_err(|_| Error::new(message)),
+        Err(_) => Err(Error::new(message)),
+    }
+}
+
+/// Queries the most recently created `OrderStatus::Pending` order of a user.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `user_id` - UUID of user to query current pending order of.
+async fn query_current_pending_order(
+    collection: &Collection<Order>,
+    user_id: Uuid,
+) -> Result<Option<Order>> {
+    let filter = doc! {"user._id": user_id, "order_status": OrderStatus::Pending};
+    let find_one_options = FindOneOptions::builder()
+        .sort(doc! {"created_at": -1})
+        .build();
+    match collection.find_one(filter, find_one_options).await {
+        Ok(maybe_order) => Ok(maybe_order),
         Err(_) => {
-            let message = format!("OrderItem with UUID: `{}` not found.", id);
+            let message = format!(
+                "Pending order of user with UUID: `{}` could not be retrieved.",
+                user_id
+            );
             Err(Error::new(message))
         }
     }
 }
 
+/// Number of hexadecimal digits of a UUID.
+const UUID_HEX_DIGITS: usize = 32;
+
+/// Builds the range of UUIDs starting with a prefix of hexadecimal digits, the lowest and the highest UUID with the prefix.
+///
+/// UUIDs are stored as binary, which MongoDB compares bytewise, so that the range matches exactly the UUIDs with the prefix.
+/// Hyphens and surrounding whitespace are ignored, the prefix is case-insensitive.
+///
+/// * `query` - Prefix of the UUID, e.g. `3a4f1bd6-0b`.
+fn build_uuid_prefix_range(query: &str) -> Result<(Uuid, Uuid)> {
+    let prefix: String = query.trim().chars().filter(|c| *c != '-').collect();
+    if prefix.is_empty()
+        || prefix.len() > UUID_HEX_DIGITS
+        || !prefix.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        let message = format!(
+            "Search query: `{}` is not a prefix of a UUID, it must consist of 1 to 32 hexadecimal digits.",
+            query
+        );
+        return Err(Error::new(message));
+    }
+    let padding_length = UUID_HEX_DIGITS - prefix.len();
+    let lowest_id = Uuid::parse_str(format!("{}{}", prefix, "0".repeat(padding_length)))?;
+    let highest_id = Uuid::parse_str(format!("{}{}", prefix, "f".repeat(padding_length)))?;
+    Ok((lowest_id, highest_id))
+}
+
+/// Shared function to query a paginated connection of orders matching a filter.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `filter` - MongoDB filter document which the orders need to match.
+/// * `first` - Describes that the `first` N orders should be retrieved, clamped to the maximum page size.
+/// * `skip` - Describes how many orders should be skipped at the beginning.
+/// * `order_by` - Specifies the order in which orders are retrieved.
+pub async fn query_order_connection(
+    collection: &Collection<Order>,
+    filter: Document,
+    first: Option<u32>,
+    skip: Option<u64>,
+    order_by: Option<OrderOrderInput>,
+) -> Result<OrderConnection> {
+    let order_order = order_by.unwrap_or_default();
+    let sorting_doc = doc! {order_order.field.unwrap_or_default().as_str(): i32::from(order_order.direction.unwrap_or_default())};
+    let find_options = FindOptions::builder()
+        .skip(skip)
+        .limit(i64::try_from(clamp_page_size(
+            first.map(|first| first as usize),
+        ))?)
+        .sort(sorting_doc)
+        .build();
+    let document_collection = collection.clone_with_type::<Document>();
+    let maybe_find_results: Result<FindResult<Order>, CursorError> =
+        PaginatedCursor::new(Some(find_options.clone()), None, None)
+            .find(&document_collection, Some(&filter))
+            .await;
+    match maybe_find_results {
+        Ok(find_results) => {
+            let find_result_wrapper = FindResultWrapper(find_results);
+            let connection = Into::<BaseConnection<Order>>::into(find_result_wrapper);
+            Ok(Into::<OrderConnection>::into(connection))
+        }
+        Err(_) => Err(Error::new("Retrieving orders failed in MongoDB.")),
+    }
+}
+
 /// Shared function to query an object: `T` from a MongoDB collection of object: `T`.
 ///
 /// * `connection` - MongoDB database connection.
@@ -115,21 +479,47 @@ pub async fn query_object<T: for<'a> Deserialize<'a> + Unpin + Send + Sync>(
     collection: &Collection<T>,
     id: Uuid,
 ) -> Result<T> {
+    let message = format!("{} with UUID: `{}` not found.", domain_type_name::<T>(), id);
     match collection.find_one(doc! {"_id": id }, None).await {
         Ok(maybe_object) => match maybe_object {
             Some(object) => Ok(object),
             None => {
-                let message = format!("{} with UUID: `{}` not found.", type_name::<T>(), id);
+                info!("{} with UUID: `{}` not found.", type_name::<T>(), id);
                 Err(Error::new(message))
             }
         },
-        Err(_) => {
-            let message = format!("{} with UUID: `{}` not found.", type_name::<T>(), id);
+        Err(error) => {
+            info!(
+                "{} with UUID: `{}` could not be queried: {:?}",
+                type_name::<T>(),
+                id,
+                error
+            );
             Err(Error::new(message))
         }
     }
 }
 
+/// Maps a type to its client-facing domain name, which does not leak the internal module path.
+///
+/// For example `misarch_order::graphql::model::order_item::OrderItem` is mapped to `Order item`.
+pub fn domain_type_name<T>() -> String {
+    let type_name = type_name::<T>();
+    let prefixless_type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+    prefixless_type_name.chars().enumerate().fold(
+        String::new(),
+        |mut domain_type_name, (index, character)| {
+            if index > 0 && character.is_uppercase() {
+                domain_type_name.push(' ');
+                domain_type_name.extend(character.to_lowercase());
+            } else {
+                domain_type_name.push(character);
+            }
+            domain_type_name
+        },
+    )
+}
+
 /// Shared function to query objects: `T` from a MongoDB collection of object: `T`.
 ///
 /// * `connection` - MongoDB database connection.
@@ -155,13 +545,54 @@ where
                 .await?;
             Ok(objects)
         }
-        Err(_) => {
+        Err(error) => {
+            info!(
+                "{} with UUIDs: `{:?}` could not be queried: {:?}",
+                type_name::<T>(),
+                object_ids,
+                error
+            );
             let message = format!(
                 "{} with UUIDs: `{:?}` not found.",
-                type_name::<T>(),
+                domain_type_name::<T>(),
                 object_ids
             );
             Err(Error::new(message))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uuid_prefix_range_contains_uuids_with_prefix() {
+        let (lowest_id, highest_id) = build_uuid_prefix_range(" 3A4F1BD6-0b ").unwrap();
+        assert_eq!(
+            lowest_id.to_string(),
+            "3a4f1bd6-0b00-0000-0000-000000000000"
+        );
+        assert_eq!(
+            highest_id.to_string(),
+            "3a4f1bd6-0bff-ffff-ffff-ffffffffffff"
+        );
+        let id = Uuid::parse_str("3a4f1bd6-0bcb-4f5c-9a43-5b0e7b36c0f5").unwrap();
+        assert!(lowest_id.bytes() <= id.bytes() && id.bytes() <= highest_id.bytes());
+    }
+
+    #[test]
+    fn uuid_prefix_range_of_complete_uuid_matches_exactly() {
+        let (lowest_id, highest_id) =
+            build_uuid_prefix_range("3a4f1bd6-0bcb-4f5c-9a43-5b0e7b36c0f5").unwrap();
+        assert_eq!(lowest_id, highest_id);
+    }
+
+    #[test]
+    fn invalid_uuid_prefixes_are_rejected() {
+        assert!(build_uuid_prefix_range("").is_err());
+        assert!(build_uuid_prefix_range("3a4g").is_err());
+        assert!(build_uuid_prefix_range("ä").is_err());
+        assert!(build_uuid_prefix_range(&"a".repeat(33)).is_err());
+    }
+}
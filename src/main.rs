@@ -1,4 +1,9 @@
-use std::{env, fs::File, io::Write};
+use std::{
+    env,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use async_graphql::{
     extensions::Logger, http::GraphiQLSource, EmptySubscription, SDLExportOptions, Schema,
@@ -8,7 +13,10 @@ use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 
 use axum::{
     extract::State,
-    http::{header::HeaderMap, StatusCode},
+    http::{
+        header::{HeaderMap, CONTENT_TYPE},
+        HeaderValue, Method, StatusCode,
+    },
     response::{self, IntoResponse},
     routing::{get, post},
     Router, Server,
@@ -16,34 +24,59 @@ use axum::{
 
 use clap::{arg, command, Parser};
 
-use log::{info, Level};
 use mongodb::{options::ClientOptions, Client, Database};
+use tower_http::cors::{Any, CorsLayer};
+use tracing::{info, warn};
+use tracing_subscriber::EnvFilter;
 
 mod authorization;
-use authorization::AuthorizedUserHeader;
+mod collection_names;
+use authorization::{authentication_required, is_public_request, AuthorizedUserHeader};
+use collection_names::{
+    COUPONS, ORDERS, ORDER_COMPENSATIONS, PAYMENT_INFORMATIONS, PRODUCT_VARIANTS,
+    PRODUCT_VARIANT_VERSIONS, SHIPMENT_METHODS, TAX_RATES, USERS,
+};
+
+mod correlation_id;
+use correlation_id::CorrelationId;
 
+mod dapr;
 mod event;
 mod graphql;
+mod metrics;
+mod order_event_replay;
+mod order_export;
+mod rounding_strategy;
+use rounding_strategy::RoundingStrategy;
+mod store_config;
+use store_config::StoreConfig;
+#[cfg(test)]
+mod test_fixtures;
 
+use event::event_config::EventConfig;
 use event::{
     http_event_service::{
-        list_topic_subscriptions, on_id_creation_event, on_product_variant_update_event,
-        on_product_variant_version_creation_event, on_shipment_creation_failed_event,
-        on_tax_rate_version_creation_event, on_user_address_archived_event,
-        on_user_address_creation_event, HttpEventServiceState,
+        event_handler_routes, list_topic_subscriptions, verify_topic_subscription_routes,
+        HttpEventServiceState,
     },
-    order_compensation::OrderCompensation,
+    order_compensation::{republish_unpublished_order_compensations, OrderCompensation},
 };
 use graphql::{
     model::{
-        foreign_types::{Coupon, ProductVariant, ShipmentMethod, TaxRate},
+        foreign_types::{
+            Coupon, PaymentInformation, ProductVariant, ProductVariantVersion, ShipmentMethod,
+            TaxRate,
+        },
         order::Order,
         user::User,
     },
-    mutation::Mutation,
+    mutation::{republish_unpublished_order_created_events, Mutation},
     query::Query,
 };
 
+/// Default name of the MongoDB database.
+const DEFAULT_DATABASE_NAME: &str = "order-database";
+
 /// Builds the GraphiQL frontend.
 async fn graphiql() -> impl IntoResponse {
     response::Html(GraphiQLSource::build().endpoint("/").finish())
@@ -66,6 +99,54 @@ async fn db_connection() -> Client {
     Client::with_options(client_options).unwrap()
 }
 
+/// Builds the CORS layer for the GraphQL endpoint.
+///
+/// Allowed origins are read from the comma-separated `$CORS_ALLOWED_ORIGINS`.
+/// If `$CORS_ALLOWED_ORIGINS` is not set, no cross-origin requests are allowed.
+/// Origins which are not valid header values are logged as a warning and skipped.
+fn build_cors_layer() -> CorsLayer {
+    let allowed_origins: Vec<HeaderValue> = match env::var("CORS_ALLOWED_ORIGINS") {
+        Ok(origins) => parse_cors_allowed_origins(&origins),
+        Err(env::VarError::NotUnicode(_)) => {
+            warn!("`$CORS_ALLOWED_ORIGINS` is not valid unicode, no cross-origin requests are allowed.");
+            vec![]
+        }
+        Err(env::VarError::NotPresent) => vec![],
+    };
+    CorsLayer::new()
+        .allow_origin(allowed_origins)
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers(Any)
+}
+
+/// Parses comma-separated allowed origins, skipping empty and invalid origins.
+///
+/// * `origins` - Comma-separated allowed origins, e.g. `https://a.example, https://b.example`.
+fn parse_cors_allowed_origins(origins: &str) -> Vec<HeaderValue> {
+    origins
+        .split(',')
+        .map(|origin| origin.trim())
+        .filter(|origin| !origin.is_empty())
+        .filter_map(|origin| match origin.parse::<HeaderValue>() {
+            Ok(header_value) => Some(header_value),
+            Err(error) => {
+                warn!(
+                    "Skipping CORS allowed origin: `{}` of `$CORS_ALLOWED_ORIGINS`: {}",
+                    origin, error
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Returns the name of the MongoDB database.
+///
+/// Read from `$MONGODB_DATABASE`, defaults to `DEFAULT_DATABASE_NAME`.
+fn database_name() -> String {
+    env::var("MONGODB_DATABASE").unwrap_or(DEFAULT_DATABASE_NAME.to_string())
+}
+
 /// Returns Router that establishes connection to Dapr.
 ///
 /// Creates endpoints to define pub/sub interaction with Dapr.
@@ -73,54 +154,45 @@ async fn db_connection() -> Client {
 /// * `db_client` - MongoDB database client.
 async fn build_dapr_router(db_client: Database) -> Router {
     let product_variant_collection: mongodb::Collection<ProductVariant> =
-        db_client.collection::<ProductVariant>("product_variants");
-    let coupon_collection: mongodb::Collection<Coupon> = db_client.collection::<Coupon>("coupons");
+        db_client.collection::<ProductVariant>(PRODUCT_VARIANTS);
+    let product_variant_version_collection: mongodb::Collection<ProductVariantVersion> =
+        db_client.collection::<ProductVariantVersion>(PRODUCT_VARIANT_VERSIONS);
+    let coupon_collection: mongodb::Collection<Coupon> = db_client.collection::<Coupon>(COUPONS);
     let tax_rate_collection: mongodb::Collection<TaxRate> =
-        db_client.collection::<TaxRate>("tax_rates");
+        db_client.collection::<TaxRate>(TAX_RATES);
     let shipment_method_collection: mongodb::Collection<ShipmentMethod> =
-        db_client.collection::<ShipmentMethod>("shipment_methods");
-    let user_collection: mongodb::Collection<User> = db_client.collection::<User>("users");
-    let order_collection: mongodb::Collection<Order> = db_client.collection::<Order>("orders");
+        db_client.collection::<ShipmentMethod>(SHIPMENT_METHODS);
+    let user_collection: mongodb::Collection<User> = db_client.collection::<User>(USERS);
+    let payment_information_collection: mongodb::Collection<PaymentInformation> =
+        db_client.collection::<PaymentInformation>(PAYMENT_INFORMATIONS);
+    let order_collection: mongodb::Collection<Order> = db_client.collection::<Order>(ORDERS);
     let order_compensation_collection: mongodb::Collection<OrderCompensation> =
-        db_client.collection::<OrderCompensation>("order_compensations");
+        db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS);
 
     // Define routes.
-    let app = Router::new()
-        .route("/dapr/subscribe", get(list_topic_subscriptions))
-        .route("/on-id-creation-event", post(on_id_creation_event))
-        .route(
-            "/on-product-variant-version-creation-event",
-            post(on_product_variant_version_creation_event),
-        )
-        .route(
-            "/on-product-variant-updated-event",
-            post(on_product_variant_update_event),
-        )
-        .route(
-            "/on-tax-rate-version-creation-event",
-            post(on_tax_rate_version_creation_event),
-        )
-        .route(
-            "/on-user-address-creation-event",
-            post(on_user_address_creation_event),
-        )
-        .route(
-            "/on-user-address-archived-event",
-            post(on_user_address_archived_event),
-        )
-        .route(
-            "/on-shipment-creation-failed-event",
-            post(on_shipment_creation_failed_event),
-        )
-        .with_state(HttpEventServiceState {
-            product_variant_collection,
-            coupon_collection,
-            tax_rate_collection,
-            shipment_method_collection,
-            user_collection,
-            order_collection,
-            order_compensation_collection,
-        });
+    let event_handler_routes = event_handler_routes();
+    verify_topic_subscription_routes(
+        &event_handler_routes
+            .iter()
+            .map(|(route, _)| *route)
+            .collect::<Vec<&str>>(),
+    );
+    let app = event_handler_routes.into_iter().fold(
+        Router::new().route("/dapr/subscribe", get(list_topic_subscriptions)),
+        |router, (route, handler)| router.route(route, handler),
+    );
+    let app = app.with_state(HttpEventServiceState {
+        product_variant_collection,
+        product_variant_version_collection,
+        coupon_collection,
+        tax_rate_collection,
+        shipment_method_collection,
+        user_collection,
+        payment_information_collection,
+        order_collection,
+        order_compensation_collection,
+        event_config: EventConfig::from_env(),
+    });
     app
 }
 
@@ -128,33 +200,95 @@ async fn build_dapr_router(db_client: Database) -> Router {
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Generates GraphQL schema in `./schemas/order.graphql`.
+    /// Generates GraphQL schema in the schema output path.
     #[arg(long)]
     generate_schema: bool,
+    /// Path of the generated GraphQL schema, missing parent directories are created.
+    #[arg(long, default_value = DEFAULT_SCHEMA_OUTPUT_PATH)]
+    schema_output: PathBuf,
 }
 
-/// Activates logger and parses argument for optional schema generation. Otherwise starts gRPC and GraphQL server.
+/// Default path of the GraphQL schema generated with `--generate-schema`.
+const DEFAULT_SCHEMA_OUTPUT_PATH: &str = "./schemas/order.graphql";
+
+/// Default log level directive, used if `$RUST_LOG` is not set.
+const DEFAULT_LOG_LEVEL: &str = "warn";
+
+/// Activates structured logging with spans.
+///
+/// The log level is read from `$RUST_LOG` and defaults to `DEFAULT_LOG_LEVEL`.
+fn init_tracing() {
+    let env_filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_LOG_LEVEL));
+    tracing_subscriber::fmt().with_env_filter(env_filter).init();
+}
+
+/// Activates logger and parses arguments for optional schema generation. Otherwise starts gRPC and GraphQL server.
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
-    simple_logger::init_with_level(Level::Warn).unwrap();
+    init_tracing();
 
     let args = Args::parse();
     if args.generate_schema {
         let schema = Schema::build(Query, Mutation, EmptySubscription).finish();
-        let mut file = File::create("./schemas/order.graphql")?;
-        let sdl_export_options = SDLExportOptions::new().federation();
-        let schema_sdl = schema.sdl_with_options(sdl_export_options);
-        file.write_all(schema_sdl.as_bytes())?;
-        info!("GraphQL schema: ./schemas/order.graphql was successfully generated!");
+        write_schema(&args.schema_output, &federation_sdl(&schema))?;
+        info!(
+            "GraphQL schema: {} was successfully generated!",
+            args.schema_output.display()
+        );
     } else {
         start_service().await;
     }
     Ok(())
 }
 
+/// Writes the GraphQL schema to a path, creating missing parent directories.
+///
+/// Returns an error describing the path if it is not writable.
+///
+/// * `path` - Path to write the GraphQL schema to.
+/// * `schema_sdl` - SDL of the GraphQL schema.
+fn write_schema(path: &Path, schema_sdl: &str) -> std::io::Result<()> {
+    let not_writable_error = |error: std::io::Error| {
+        std::io::Error::new(
+            error.kind(),
+            format!(
+                "Schema output path: `{}` is not writable: {}",
+                path.display(),
+                error
+            ),
+        )
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(not_writable_error)?;
+    }
+    let mut file = File::create(path).map_err(not_writable_error)?;
+    file.write_all(schema_sdl.as_bytes())
+        .map_err(not_writable_error)
+}
+
+/// Exports the federation SDL of the GraphQL schema.
+///
+/// * `schema` - GraphQL schema to export.
+fn federation_sdl(schema: &Schema<Query, Mutation, EmptySubscription>) -> String {
+    let sdl_export_options = SDLExportOptions::new().federation();
+    schema.sdl_with_options(sdl_export_options)
+}
+
+/// HTTP endpoint returning the federation SDL of the GraphQL schema as plain text, e.g. for gateways.
+///
+/// * `schema` - GraphQL schema to export.
+async fn schema_sdl(
+    State(schema): State<Schema<Query, Mutation, EmptySubscription>>,
+) -> impl IntoResponse {
+    ([(CONTENT_TYPE, "text/plain")], federation_sdl(&schema))
+}
+
 /// Describes the handler for GraphQL requests.
 ///
 /// Parses the "Authenticate-User" header and writes it in the context data of the specfic request.
+/// If `$AUTH_REQUIRED` is set, requests without a valid header are rejected with `StatusCode::UNAUTHORIZED`, unless they only select public root query fields.
+/// Reads or generates the correlation id of the request and writes it in the context data as well.
 /// Then executes the GraphQL schema with the request.
 ///
 /// * `schema` - GraphQL schema used by handler.
@@ -164,31 +298,71 @@ async fn graphql_handler(
     State(schema): State<Schema<Query, Mutation, EmptySubscription>>,
     headers: HeaderMap,
     req: GraphQLRequest,
-) -> GraphQLResponse {
+) -> Result<GraphQLResponse, StatusCode> {
     let mut req = req.into_inner();
-    if let Ok(authenticate_user_header) = AuthorizedUserHeader::try_from(&headers) {
-        req = req.data(authenticate_user_header);
+    match AuthorizedUserHeader::from_header_map(&headers) {
+        Ok(Some(authenticate_user_header)) => req = req.data(authenticate_user_header),
+        Ok(None) | Err(_) if authentication_required() && !is_public_request(&req.query) => {
+            return Err(StatusCode::UNAUTHORIZED)
+        }
+        Ok(None) | Err(_) => (),
     }
-    schema.execute(req).await.into()
+    let correlation_id = CorrelationId::from(&headers);
+    info!(
+        "Handling GraphQL request with correlation id: `{}`",
+        correlation_id.0
+    );
+    req = req.data(correlation_id);
+    Ok(schema.execute(req).await.into())
 }
 
 /// Starts order service on port 8000.
 async fn start_service() {
     let client = db_connection().await;
-    let db_client: Database = client.database("order-database");
+    let db_client: Database = client.database(&database_name());
 
     let schema = Schema::build(Query, Mutation, EmptySubscription)
         .extension(Logger)
         .data(db_client.clone())
+        .data(EventConfig::from_env())
+        .data(StoreConfig::from_env())
+        .data(RoundingStrategy::from_env())
         .enable_federation()
         .finish();
 
     let graphiql = Router::new()
         .route("/", get(graphiql).post(graphql_handler))
         .route("/health", get(StatusCode::OK))
-        .with_state(schema);
+        .route("/schema", get(schema_sdl))
+        .route("/metrics", get(metrics::metrics))
+        .with_state(schema)
+        .layer(build_cors_layer());
+    let export_router = Router::new()
+        .route("/orders/export", get(order_export::export_orders))
+        .with_state(db_client.collection::<Order>(ORDERS));
+    let order_event_replay_router = Router::new()
+        .route(
+            "/admin/replay-order-event/:order_id",
+            post(order_event_replay::replay_order_event),
+        )
+        .with_state(order_event_replay::OrderEventReplayState {
+            collection: db_client.collection::<Order>(ORDERS),
+            event_config: EventConfig::from_env(),
+        });
+    tokio::spawn(republish_unpublished_order_created_events(
+        db_client.collection::<Order>(ORDERS),
+        EventConfig::from_env(),
+    ));
+    tokio::spawn(republish_unpublished_order_compensations(
+        db_client.collection::<OrderCompensation>(ORDER_COMPENSATIONS),
+        EventConfig::from_env(),
+    ));
     let dapr_router = build_dapr_router(db_client).await;
-    let app = Router::new().merge(graphiql).merge(dapr_router);
+    let app = Router::new()
+        .merge(graphiql)
+        .merge(export_router)
+        .merge(order_event_replay_router)
+        .merge(dapr_router);
 
     info!("GraphiQL IDE: http://0.0.0.0:8080");
     Server::bind(&"0.0.0.0:8080".parse().unwrap())
@@ -196,3 +370,21 @@ async fn start_service() {
         .await
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_cors_allowed_origins_are_skipped() {
+        let allowed_origins =
+            parse_cors_allowed_origins(" https://a.example, ,https://b\nexample,https://c.example");
+        assert_eq!(
+            allowed_origins,
+            vec![
+                HeaderValue::from_static("https://a.example"),
+                HeaderValue::from_static("https://c.example")
+            ]
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+
+use crate::graphql::model::order::RejectionReason;
+
+/// All rejection reasons, which bound the label cardinality of the rejected orders counter.
+const REJECTION_REASONS: [RejectionReason; 4] = [
+    RejectionReason::InvalidOrderData,
+    RejectionReason::InventoryReservationFailed,
+    RejectionReason::PendingTimeoutExceeded,
+    RejectionReason::PaymentFailed,
+];
+
+/// Counters of rejected orders, indexed like `REJECTION_REASONS`.
+static REJECTED_ORDERS: [AtomicU64; 4] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+/// Returns the index of the counter of a rejection reason.
+fn rejection_reason_index(rejection_reason: RejectionReason) -> usize {
+    match rejection_reason {
+        RejectionReason::InvalidOrderData => 0,
+        RejectionReason::InventoryReservationFailed => 1,
+        RejectionReason::PendingTimeoutExceeded => 2,
+        RejectionReason::PaymentFailed => 3,
+    }
+}
+
+/// Increments the counter of rejected orders for a rejection reason.
+///
+/// * `rejection_reason` - Reason of the order rejection.
+pub fn increment_rejected_orders(rejection_reason: RejectionReason) {
+    REJECTED_ORDERS[rejection_reason_index(rejection_reason)].fetch_add(1, Ordering::Relaxed);
+}
+
+/// HTTP endpoint exposing the metrics of the service in the Prometheus text format.
+pub async fn metrics() -> impl IntoResponse {
+    let mut body = String::from(
+        "# HELP order_rejected_orders_total Total amount of rejected orders by rejection reason.\n# TYPE order_rejected_orders_total counter\n",
+    );
+    for rejection_reason in REJECTION_REASONS {
+        let count =
+            REJECTED_ORDERS[rejection_reason_index(rejection_reason)].load(Ordering::Relaxed);
+        body.push_str(&format!(
+            "order_rejected_orders_total{{reason=\"{}\"}} {}\n",
+            rejection_reason.as_str(),
+            count
+        ));
+    }
+    ([(CONTENT_TYPE, "text/plain; version=0.0.4")], body)
+}
@@ -0,0 +1,139 @@
+use std::env;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use bson::Uuid;
+use mongodb::Collection;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::{info, warn};
+
+use crate::{
+    correlation_id::{CorrelationId, CORRELATION_ID_HEADER},
+    event::{event_config::EventConfig, model::order_dto::OrderDTO},
+    graphql::{
+        model::order::{Order, OrderStatus},
+        query::query_object,
+    },
+};
+
+/// Name of the HTTP header containing the admin token.
+const ADMIN_TOKEN_HEADER: &str = "Admin-Token";
+
+/// State of the order event replay endpoint.
+#[derive(Clone)]
+pub struct OrderEventReplayState {
+    /// MongoDB collection of orders.
+    pub collection: Collection<Order>,
+    /// Configuration of the published order created event.
+    pub event_config: EventConfig,
+}
+
+/// Query parameters of the order event replay endpoint.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayOrderEventParams {
+    /// Name of the Dapr pub/sub component to publish with, defaults to `EventConfig::pubsub_name`.
+    pub pubsub_name: Option<String>,
+    /// Topic to publish to, defaults to `EventConfig::order_created_topic`.
+    pub topic: Option<String>,
+}
+
+/// Admin HTTP endpoint to replay the order created event of an order with `OrderStatus::Placed`, e.g. for integration recovery.
+///
+/// Rebuilds the order DTO and publishes it, optionally to another pub/sub component or topic to test a single subscriber.
+/// Unlike the `resendOrderCreatedEvent` mutation, the event is neither dispatched to webhooks nor marked as published.
+/// The payment authorization is not persisted and therefore not contained in the replayed event.
+/// Returns the replayed event data.
+///
+/// * `state` - MongoDB collection of orders and configuration of the published order created event.
+/// * `headers` - Header map containing the `Admin-Token` header.
+/// * `order_id` - UUID of order to replay the order created event of.
+/// * `params` - Query parameters overriding the pub/sub component and topic.
+pub async fn replay_order_event(
+    State(state): State<OrderEventReplayState>,
+    headers: HeaderMap,
+    Path(order_id): Path<Uuid>,
+    Query(params): Query<ReplayOrderEventParams>,
+) -> Result<Json<Value>, StatusCode> {
+    verify_admin_token(&headers)?;
+    let order = query_object(&state.collection, order_id)
+        .await
+        .map_err(|_| StatusCode::NOT_FOUND)?;
+    if order.order_status != OrderStatus::Placed {
+        warn!(
+            "Order created event of order of UUID: `{}` can not be replayed, `{:?}` must be `OrderStatus::Placed`.",
+            order_id, order.order_status
+        );
+        return Err(StatusCode::CONFLICT);
+    }
+    let order_dto =
+        OrderDTO::try_from((order, None)).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let payload = serde_json::to_value(order_dto).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let pubsub_name = params
+        .pubsub_name
+        .unwrap_or(state.event_config.pubsub_name.clone());
+    let topic = params
+        .topic
+        .unwrap_or(state.event_config.order_created_topic.clone());
+    let event_config = EventConfig {
+        pubsub_name,
+        ..state.event_config
+    };
+    let correlation_id = CorrelationId::from(&headers);
+    reqwest::Client::new()
+        .post(event_config.publish_url(&topic))
+        .json(&payload)
+        .header(CORRELATION_ID_HEADER, &correlation_id.0)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| {
+            warn!(
+                "Replaying order created event of order of UUID: `{}` to topic: `{}` failed: {}",
+                order_id, topic, error
+            );
+            StatusCode::BAD_GATEWAY
+        })?;
+    info!(
+        "Replayed order created event of order of UUID: `{}` to topic: `{}` of pub/sub component: `{}`.",
+        order_id, topic, event_config.pubsub_name
+    );
+    Ok(Json(payload))
+}
+
+/// Verifies the `Admin-Token` header against `$ADMIN_TOKEN`.
+///
+/// The endpoint is disabled if `$ADMIN_TOKEN` is not set or empty.
+///
+/// * `headers` - Header map containing the `Admin-Token` header.
+fn verify_admin_token(headers: &HeaderMap) -> Result<(), StatusCode> {
+    let admin_token = env::var("ADMIN_TOKEN")
+        .ok()
+        .filter(|admin_token| !admin_token.is_empty())
+        .ok_or(StatusCode::FORBIDDEN)?;
+    let provided_token = headers
+        .get(ADMIN_TOKEN_HEADER)
+        .and_then(|header_value| header_value.to_str().ok())
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    match tokens_equal(provided_token.as_bytes(), admin_token.as_bytes()) {
+        true => Ok(()),
+        false => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// Compares two tokens in constant time with respect to their content.
+///
+/// * `provided_token` - Token provided in the request.
+/// * `admin_token` - Configured admin token.
+fn tokens_equal(provided_token: &[u8], admin_token: &[u8]) -> bool {
+    provided_token.len() == admin_token.len()
+        && provided_token
+            .iter()
+            .zip(admin_token.iter())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
@@ -0,0 +1,140 @@
+use std::io;
+
+use axum::{
+    body::StreamBody,
+    extract::{Query, State},
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+use bson::{doc, Uuid};
+use futures::{stream, StreamExt, TryStreamExt};
+use mongodb::{options::FindOptions, Collection};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    authorization::{check_permissions, AuthorizedUserHeader},
+    event::model::order_dto::OrderDTO,
+    graphql::model::order::Order,
+};
+
+/// Header row of the CSV order export, each row describes an order item of an order.
+const CSV_HEADER: &str = "orderId,orderStatus,createdAt,placedAt,rejectionReason,compensatableOrderAmount,orderItemId,productVariantId,productVariantVersionId,count,compensatableAmount\n";
+
+/// Format of an order export.
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// JSON array of order DTOs.
+    #[default]
+    Json,
+    /// CSV with a row per order item.
+    Csv,
+}
+
+/// Query parameters of the order export endpoint.
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOrdersParams {
+    /// UUID of user to export orders of, defaults to the requesting user.
+    pub user_id: Option<Uuid>,
+    /// Format of the export, defaults to `ExportFormat::Json`.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// HTTP endpoint to export the order history of a user, e.g. for data requests.
+///
+/// Streams all orders of the user from a MongoDB cursor, so the orders are never loaded into memory at once.
+/// Authorizes the `Authorized-User` header against the user of the export.
+///
+/// * `collection` - MongoDB collection of orders.
+/// * `headers` - Header map containing the `Authorized-User` header.
+/// * `params` - Query parameters describing the user and format of the export.
+pub async fn export_orders(
+    State(collection): State<Collection<Order>>,
+    headers: HeaderMap,
+    Query(params): Query<ExportOrdersParams>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let authorized_user_header =
+        AuthorizedUserHeader::try_from(&headers).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let user_id = params.user_id.unwrap_or(authorized_user_header.id);
+    check_permissions(&authorized_user_header, Some(user_id)).map_err(|_| StatusCode::FORBIDDEN)?;
+    let find_options = FindOptions::builder().sort(doc! {"created_at": 1}).build();
+    let cursor = collection
+        .find(doc! {"user._id": user_id}, find_options)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    info!(
+        "Exporting orders of user of UUID: `{}` as `{:?}`.",
+        user_id, params.format
+    );
+    let order_dtos = cursor.map_ok(OrderDTO::from).map_err(io::Error::other);
+    let (content_type, body) = match params.format {
+        ExportFormat::Json => {
+            let elements = order_dtos.enumerate().map(|(index, maybe_order_dto)| {
+                let order_dto = maybe_order_dto?;
+                let separator = if index == 0 { "" } else { "," };
+                Ok(format!(
+                    "{}{}",
+                    separator,
+                    serde_json::to_string(&order_dto)?
+                ))
+            });
+            let body = stream::once(async { Ok("[".to_string()) })
+                .chain(elements)
+                .chain(stream::once(async { Ok("]".to_string()) }))
+                .boxed();
+            ("application/json", body)
+        }
+        ExportFormat::Csv => {
+            let rows = order_dtos.map_ok(|order_dto| build_csv_rows(&order_dto));
+            let body = stream::once(async { Ok(CSV_HEADER.to_string()) })
+                .chain(rows)
+                .boxed();
+            ("text/csv", body)
+        }
+    };
+    Ok(([(CONTENT_TYPE, content_type)], StreamBody::new(body)))
+}
+
+/// Builds the CSV rows of an order, one row per order item.
+///
+/// Orders without order items result in a single row without order item columns.
+///
+/// * `order_dto` - Order DTO to build CSV rows of.
+fn build_csv_rows(order_dto: &OrderDTO) -> String {
+    let order_columns = format!(
+        "{},{},{},{},{},{}",
+        order_dto.id,
+        order_dto.order_status.as_str(),
+        order_dto.created_at.to_rfc3339(),
+        order_dto
+            .placed_at
+            .map(|placed_at| placed_at.to_rfc3339())
+            .unwrap_or_default(),
+        order_dto
+            .rejection_reason
+            .map(|rejection_reason| rejection_reason.as_str())
+            .unwrap_or_default(),
+        order_dto.compensatable_order_amount
+    );
+    if order_dto.order_items.is_empty() {
+        return format!("{},,,,,\n", order_columns);
+    }
+    order_dto
+        .order_items
+        .iter()
+        .map(|order_item_dto| {
+            format!(
+                "{},{},{},{},{},{}\n",
+                order_columns,
+                order_item_dto.id,
+                order_item_dto.product_variant_id,
+                order_item_dto.product_variant_version_id,
+                order_item_dto.count,
+                order_item_dto.compensatable_amount
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use std::env;
+
+/// Strategy to round discounted amounts to minor units.
+///
+/// Read once at startup and provided in the context of the GraphQL schema.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingStrategy {
+    /// Rounds to the nearest minor unit, halves are rounded up, e.g. `899.5` to `900`.
+    HalfUp,
+    /// Rounds to the nearest minor unit, halves are rounded to the nearest even minor unit, e.g. `898.5` to `898`. Also known as banker's rounding.
+    HalfEven,
+    /// Rounds down to the next minor unit, e.g. `899.9` to `899`.
+    Floor,
+}
+
+impl RoundingStrategy {
+    /// Reads the rounding strategy from `$ROUNDING_STRATEGY`, one of `HALF_UP`, `HALF_EVEN` or `FLOOR`.
+    ///
+    /// Defaults to `RoundingStrategy::HalfUp` if unset or unknown.
+    pub fn from_env() -> Self {
+        match env::var("ROUNDING_STRATEGY")
+            .unwrap_or_default()
+            .to_uppercase()
+            .as_str()
+        {
+            "HALF_EVEN" => RoundingStrategy::HalfEven,
+            "FLOOR" => RoundingStrategy::Floor,
+            _ => RoundingStrategy::HalfUp,
+        }
+    }
+
+    /// Rounds an amount to minor units, negative amounts are clamped to zero.
+    ///
+    /// * `amount` - Amount in minor units, which may have a fraction.
+    pub fn round(&self, amount: f64) -> u64 {
+        let rounded_amount = match self {
+            RoundingStrategy::HalfUp => amount.round(),
+            RoundingStrategy::HalfEven => amount.round_ties_even(),
+            RoundingStrategy::Floor => amount.floor(),
+        };
+        rounded_amount.max(0.0) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_up_rounds_halves_up() {
+        assert_eq!(RoundingStrategy::HalfUp.round(899.5), 900);
+        assert_eq!(RoundingStrategy::HalfUp.round(898.5), 899);
+        assert_eq!(RoundingStrategy::HalfUp.round(899.1), 899);
+    }
+
+    #[test]
+    fn half_even_rounds_halves_to_even() {
+        assert_eq!(RoundingStrategy::HalfEven.round(899.5), 900);
+        assert_eq!(RoundingStrategy::HalfEven.round(898.5), 898);
+        assert_eq!(RoundingStrategy::HalfEven.round(898.6), 899);
+    }
+
+    #[test]
+    fn floor_rounds_down() {
+        assert_eq!(RoundingStrategy::Floor.round(899.9), 899);
+    }
+
+    #[test]
+    fn negative_amounts_are_clamped_to_zero() {
+        assert_eq!(RoundingStrategy::HalfUp.round(-0.6), 0);
+    }
+}
@@ -0,0 +1,48 @@
+use std::env;
+
+/// Default country of the store.
+const DEFAULT_STORE_COUNTRY: &str = "DE";
+/// Default locale of the store.
+const DEFAULT_STORE_LOCALE: &str = "de-DE";
+/// Default currency of the store.
+const DEFAULT_STORE_CURRENCY: &str = "EUR";
+
+/// Configuration of the store, which locale dependent behavior of the order service defaults to.
+///
+/// - `country` is the default country, e.g. for the format of VAT numbers.
+/// - `locale` is the default locale of localized strings like `Order.total_formatted`.
+/// - `currency` is the currency of all monetary amounts.
+/// - `base_currency` and `exchange_rate` describe the conversion of monetary amounts to the currency of the accounting.
+#[derive(Debug, Clone)]
+pub struct StoreConfig {
+    /// ISO 3166-1 alpha-2 code of the country of the store.
+    pub country: String,
+    /// BCP 47 language tag of the locale of the store.
+    pub locale: String,
+    /// ISO 4217 code of the currency of the store.
+    pub currency: String,
+    /// ISO 4217 code of the base currency, which financial reports reconcile to.
+    pub base_currency: String,
+    /// Configured exchange rate from the currency of the store to the base currency, `None` if not configured.
+    pub exchange_rate: Option<f64>,
+}
+
+impl StoreConfig {
+    /// Reads the store configuration from `$STORE_COUNTRY`, `$STORE_LOCALE`, `$STORE_CURRENCY`, `$BASE_CURRENCY` and `$EXCHANGE_RATE`.
+    ///
+    /// Defaults to a german store using `EUR`, the base currency defaults to the currency of the store.
+    /// Exchange rates which are not positive are ignored.
+    pub fn from_env() -> Self {
+        let currency = env::var("STORE_CURRENCY").unwrap_or(DEFAULT_STORE_CURRENCY.to_string());
+        Self {
+            country: env::var("STORE_COUNTRY").unwrap_or(DEFAULT_STORE_COUNTRY.to_string()),
+            locale: env::var("STORE_LOCALE").unwrap_or(DEFAULT_STORE_LOCALE.to_string()),
+            base_currency: env::var("BASE_CURRENCY").unwrap_or(currency.clone()),
+            currency,
+            exchange_rate: env::var("EXCHANGE_RATE")
+                .ok()
+                .and_then(|exchange_rate| exchange_rate.parse::<f64>().ok())
+                .filter(|exchange_rate| exchange_rate.is_finite() && *exchange_rate > 0.0),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::{BTreeSet, HashSet};
+
+use bson::{DateTime, Uuid};
+
+use crate::{
+    graphql::{
+        model::{
+            foreign_types::{ProductVariant, ProductVariantVersion, TaxRateVersion, UserAddress},
+            order::{Order, OrderStatus, StatusHistoryEntry},
+            order_item::{OrderItem, OrderItemPricing},
+            user::User,
+        },
+        mutation_input_structs::OrderItemInput,
+    },
+    rounding_strategy::RoundingStrategy,
+};
+
+/// Builds an order item of a product variant with a price per product item, a tax rate of `0.19` and no discounts.
+///
+/// * `product_variant_id` - UUID of the product variant of the order item.
+/// * `price` - Price of a single product item in minor units.
+/// * `count` - Quantity of the order item.
+pub fn build_order_item(product_variant_id: Uuid, price: u32, count: u64) -> OrderItem {
+    let product_variant_version = ProductVariantVersion {
+        _id: Uuid::new(),
+        price,
+        tax_rate_id: Uuid::new(),
+    };
+    let product_variant = ProductVariant {
+        _id: product_variant_id,
+        current_version: product_variant_version,
+        is_publicly_visible: true,
+    };
+    let tax_rate_version = TaxRateVersion {
+        _id: Uuid::new(),
+        rate: 0.19,
+        version: 1,
+    };
+    let order_item_input = OrderItemInput {
+        shopping_cart_item_id: Uuid::new(),
+        shipment_method_id: Uuid::new(),
+        coupon_ids: HashSet::new(),
+    };
+    OrderItem::new(
+        &order_item_input,
+        &product_variant,
+        &product_variant_version,
+        OrderItemPricing {
+            tax_rate_version: &tax_rate_version,
+            internal_discounts: &BTreeSet::new(),
+            rounding_strategy: RoundingStrategy::HalfUp,
+        },
+        count,
+        DateTime::now(),
+    )
+}
+
+/// Builds an order with `OrderStatus::Pending` of order items and shipment fees.
+///
+/// * `order_items` - Order items of the order.
+/// * `shipping_total` - Total shipment fees of the order.
+pub fn build_order(order_items: Vec<OrderItem>, shipping_total: u64) -> Order {
+    let current_timestamp = DateTime::now();
+    let compensatable_order_amount = order_items
+        .iter()
+        .map(|order_item| order_item.compensatable_amount)
+        .sum();
+    let user_address_id = Uuid::new();
+    Order {
+        _id: Uuid::new(),
+        user: User {
+            _id: Uuid::new(),
+            user_address_ids: vec![user_address_id],
+        },
+        created_at: current_timestamp,
+        order_status: OrderStatus::Pending,
+        placed_at: None,
+        last_updated_at: Some(current_timestamp),
+        rejection_reason: None,
+        internal_order_items: order_items,
+        shipment_address: UserAddress::from(user_address_id),
+        invoice_address: UserAddress::from(user_address_id),
+        is_gift: false,
+        gift_recipient_address: None,
+        compensatable_order_amount,
+        shipping_total,
+        payment_information_id: Uuid::new(),
+        vat_number: None,
+        customer_note: None,
+        status_history: vec![StatusHistoryEntry::new(
+            OrderStatus::Pending,
+            current_timestamp,
+        )],
+        event_published: false,
+        payment_authorization_provided: false,
+        currency_conversion: None,
+    }
+}
@@ -0,0 +1,326 @@
+//! Test harness running the order service against a MongoDB testcontainer and a mocked Dapr sidecar.
+//!
+//! The Dapr sidecar is mocked by a mock server, which answers the service invocations of the shopping cart, inventory, discount and shipment services and accepts published events.
+//! Requires a running Docker daemon.
+
+use std::{
+    process::{Child, Command},
+    time::Duration,
+};
+
+use bson::{doc, Document, Uuid};
+use mongodb::{Client, Database};
+use serde_json::{json, Value};
+use testcontainers::{clients::Cli, core::WaitFor, Container, GenericImage};
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+/// Port of the GraphQL endpoint of the order service.
+const SERVICE_PORT: u16 = 8080;
+/// Port of MongoDB inside the testcontainer.
+const MONGODB_PORT: u16 = 27017;
+/// Name of the MongoDB database of the order service.
+const DATABASE_NAME: &str = "order-database-test";
+/// Maximum amount of health checks until the order service is expected to be started.
+const MAX_HEALTH_CHECKS: u32 = 100;
+
+/// UUIDs of the foreign types seeded in MongoDB, which are otherwise populated with events.
+pub struct ForeignTypes {
+    /// UUID of the ordering user.
+    pub user_id: Uuid,
+    /// UUID of the address registered under the user, used as shipment and invoice address.
+    pub user_address_id: Uuid,
+    /// UUID of the payment information of the user.
+    pub payment_information_id: Uuid,
+    /// UUID of the enabled shipment method.
+    pub shipment_method_id: Uuid,
+    /// UUID of the publicly visible product variant.
+    pub product_variant_id: Uuid,
+}
+
+/// Order service started against a MongoDB testcontainer and a mocked Dapr sidecar.
+///
+/// The order service is stopped when the harness is dropped.
+pub struct TestHarness<'d> {
+    /// MongoDB testcontainer, which is removed when dropped.
+    _mongodb_container: Container<'d, GenericImage>,
+    /// MongoDB database of the order service.
+    pub db_client: Database,
+    /// Mock server of the Dapr sidecar.
+    pub dapr_sidecar: MockServer,
+    /// Process of the order service.
+    service: Child,
+}
+
+impl<'d> TestHarness<'d> {
+    /// Starts a MongoDB testcontainer, the mocked Dapr sidecar and the order service, and waits until the order service is healthy.
+    ///
+    /// Republication of unpublished events is disabled, so that only events published by the tested requests reach the mocked Dapr sidecar.
+    ///
+    /// * `docker` - Docker client running the MongoDB testcontainer.
+    pub async fn start(docker: &'d Cli) -> Self {
+        let mongodb_image = GenericImage::new("mongo", "7.0")
+            .with_exposed_port(MONGODB_PORT)
+            .with_wait_for(WaitFor::message_on_stdout("Waiting for connections"));
+        let mongodb_container = docker.run(mongodb_image);
+        let uri = format!(
+            "mongodb://localhost:{}",
+            mongodb_container.get_host_port_ipv4(MONGODB_PORT)
+        );
+        let db_client = Client::with_uri_str(&uri)
+            .await
+            .unwrap()
+            .database(DATABASE_NAME);
+        let dapr_sidecar = MockServer::start().await;
+        let service = Command::new(env!("CARGO_BIN_EXE_misarch-order"))
+            .env("MONGODB_URI", &uri)
+            .env("MONGODB_DATABASE", DATABASE_NAME)
+            .env("DAPR_HTTP_PORT", dapr_sidecar.address().port().to_string())
+            .env("ORDER_CREATED_REPUBLISH_INTERVAL_SECONDS", "0")
+            .env("ORDER_COMPENSATION_REPUBLISH_INTERVAL_SECONDS", "0")
+            .spawn()
+            .unwrap();
+        let harness = Self {
+            _mongodb_container: mongodb_container,
+            db_client,
+            dapr_sidecar,
+            service,
+        };
+        harness.wait_until_healthy().await;
+        harness
+    }
+
+    /// Polls the health endpoint of the order service until it responds successfully.
+    async fn wait_until_healthy(&self) {
+        let health_url = format!("http://localhost:{}/health", SERVICE_PORT);
+        for _ in 0..MAX_HEALTH_CHECKS {
+            if let Ok(response) = reqwest::get(&health_url).await {
+                if response.status().is_success() {
+                    return;
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("Order service did not become healthy.");
+    }
+
+    /// Seeds the foreign types an order references in MongoDB.
+    ///
+    /// The product variant is priced with `price` and references a tax rate of `0.19`.
+    ///
+    /// * `price` - Price of the current version of the product variant in minor units.
+    pub async fn seed_foreign_types(&self, price: u32) -> ForeignTypes {
+        let foreign_types = ForeignTypes {
+            user_id: Uuid::new(),
+            user_address_id: Uuid::new(),
+            payment_information_id: Uuid::new(),
+            shipment_method_id: Uuid::new(),
+            product_variant_id: Uuid::new(),
+        };
+        let tax_rate_id = Uuid::new();
+        self.insert(
+            "users",
+            doc! {"_id": foreign_types.user_id, "user_address_ids": [foreign_types.user_address_id]},
+        )
+        .await;
+        self.insert(
+            "payment_informations",
+            doc! {"_id": foreign_types.payment_information_id, "user_id": foreign_types.user_id},
+        )
+        .await;
+        self.insert(
+            "shipment_methods",
+            doc! {"_id": foreign_types.shipment_method_id, "enabled": true},
+        )
+        .await;
+        self.insert(
+            "tax_rates",
+            doc! {
+                "_id": tax_rate_id,
+                "current_version": {"_id": Uuid::new(), "rate": 0.19, "version": 1},
+                "active": true,
+            },
+        )
+        .await;
+        self.insert(
+            "product_variants",
+            doc! {
+                "_id": foreign_types.product_variant_id,
+                "current_version": {
+                    "_id": Uuid::new(),
+                    "price": price,
+                    "tax_rate_id": tax_rate_id,
+                },
+                "is_publicly_visible": true,
+            },
+        )
+        .await;
+        foreign_types
+    }
+
+    /// Inserts a document in a MongoDB collection of the order service.
+    ///
+    /// * `collection_name` - Name of the collection, as defined in `collection_names`.
+    /// * `document` - Document to insert.
+    async fn insert(&self, collection_name: &str, document: Document) {
+        self.db_client
+            .collection::<Document>(collection_name)
+            .insert_one(document, None)
+            .await
+            .unwrap();
+    }
+
+    /// Mocks the shopping cart of a user containing a single shopping cart item.
+    ///
+    /// * `shopping_cart_item_id` - UUID of the shopping cart item.
+    /// * `product_variant_id` - UUID of the product variant of the shopping cart item.
+    /// * `count` - Count of the shopping cart item.
+    pub async fn mock_shopping_cart(
+        &self,
+        shopping_cart_item_id: Uuid,
+        product_variant_id: Uuid,
+        count: u64,
+    ) {
+        let data = json!({
+            "_entities": [{
+                "__typename": "User",
+                "shoppingcart": {
+                    "shoppingcartItems": {
+                        "nodes": [{
+                            "id": shopping_cart_item_id.to_string(),
+                            "productVariant": {"id": product_variant_id.to_string()},
+                            "count": count,
+                        }]
+                    }
+                }
+            }]
+        });
+        self.mock_service_invocation("/v1.0/invoke/shoppingcart/method/", data)
+            .await;
+    }
+
+    /// Mocks the unreserved stock count of a product variant.
+    ///
+    /// * `product_variant_id` - UUID of the product variant.
+    /// * `inventory_count` - Unreserved stock count of the product variant.
+    pub async fn mock_inventory(&self, product_variant_id: Uuid, inventory_count: u64) {
+        let data = json!({
+            "_entities": [{
+                "__typename": "ProductVariant",
+                "id": product_variant_id.to_string(),
+                "inventoryCount": inventory_count,
+            }]
+        });
+        self.mock_service_invocation("/v1.0/invoke/inventory/method/graphql", data)
+            .await;
+    }
+
+    /// Mocks the discount service to find no applicable discounts for a product variant.
+    ///
+    /// * `product_variant_id` - UUID of the product variant.
+    pub async fn mock_no_discounts(&self, product_variant_id: Uuid) {
+        let data = json!({
+            "findApplicableDiscounts": [{
+                "productVariantId": product_variant_id.to_string(),
+                "discounts": [],
+            }]
+        });
+        self.mock_service_invocation("/v1.0/invoke/discount/method/graphql", data)
+            .await;
+    }
+
+    /// Mocks the total shipment fees calculated by the shipment service.
+    ///
+    /// * `shipment_fees` - Total shipment fees in minor units.
+    pub async fn mock_shipment_fees(&self, shipment_fees: u64) {
+        let data = json!({ "calculateShipmentFees": shipment_fees });
+        self.mock_service_invocation("/v1.0/invoke/shipment/method/graphql", data)
+            .await;
+    }
+
+    /// Mocks a GraphQL service invocation through the Dapr sidecar.
+    ///
+    /// * `invocation_path` - Path of the service invocation.
+    /// * `data` - Response data of the GraphQL request.
+    async fn mock_service_invocation(&self, invocation_path: &str, data: Value) {
+        Mock::given(method("POST"))
+            .and(path(invocation_path))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": data })))
+            .mount(&self.dapr_sidecar)
+            .await;
+    }
+
+    /// Accepts publications to a topic of the default Dapr pub/sub component, expecting exactly `expected_publications`.
+    ///
+    /// The expectation is checked by `MockServer::verify`.
+    ///
+    /// * `topic` - Topic to accept publications to.
+    /// * `expected_publications` - Expected amount of publications to the topic.
+    pub async fn expect_publications(&self, topic: &str, expected_publications: u64) {
+        Mock::given(method("POST"))
+            .and(path(format!("/v1.0/publish/pubsub/{}", topic)))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(expected_publications)
+            .mount(&self.dapr_sidecar)
+            .await;
+    }
+
+    /// Returns the bodies of all events published to a topic of the default Dapr pub/sub component.
+    ///
+    /// * `topic` - Topic the events were published to.
+    pub async fn published_events(&self, topic: &str) -> Vec<Value> {
+        let publish_path = format!("/v1.0/publish/pubsub/{}", topic);
+        self.dapr_sidecar
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| request.url.path() == publish_path)
+            .map(|request| request.body_json().unwrap())
+            .collect()
+    }
+
+    /// Executes a GraphQL request against the order service as a buyer and returns its response data.
+    ///
+    /// Panics if the response contains errors.
+    ///
+    /// * `query` - GraphQL query or mutation.
+    /// * `variables` - Variables of the GraphQL request.
+    /// * `user_id` - UUID of the user sending the request, forwarded in the `Authorized-User` header.
+    pub async fn graphql(&self, query: &str, variables: Value, user_id: Uuid) -> Value {
+        let response = self.graphql_response(query, variables, user_id).await;
+        if let Some(errors) = response.get("errors") {
+            panic!("GraphQL request failed: {}", errors);
+        }
+        response["data"].clone()
+    }
+
+    /// Executes a GraphQL request against the order service as a buyer and returns its whole response, including errors.
+    ///
+    /// * `query` - GraphQL query or mutation.
+    /// * `variables` - Variables of the GraphQL request.
+    /// * `user_id` - UUID of the user sending the request, forwarded in the `Authorized-User` header.
+    pub async fn graphql_response(&self, query: &str, variables: Value, user_id: Uuid) -> Value {
+        let authorized_user_header = json!({"id": user_id.to_string(), "roles": ["buyer"]});
+        reqwest::Client::new()
+            .post(format!("http://localhost:{}/", SERVICE_PORT))
+            .header("Authorized-User", authorized_user_header.to_string())
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+}
+
+impl Drop for TestHarness<'_> {
+    /// Stops the order service.
+    fn drop(&mut self) {
+        let _ = self.service.kill();
+        let _ = self.service.wait();
+    }
+}
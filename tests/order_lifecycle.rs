@@ -0,0 +1,164 @@
+mod common;
+
+use bson::{doc, Document, Uuid};
+use serde_json::json;
+use testcontainers::clients::Cli;
+
+use common::{ForeignTypes, TestHarness};
+
+/// Topic of the order created event.
+const ORDER_CREATED_TOPIC: &str = "order/order/created";
+
+const CREATE_ORDER: &str = "
+    mutation CreateOrder($input: CreateOrderInput!) {
+        createOrder(input: $input) {
+            id
+            orderStatus
+            grandTotal
+        }
+    }
+";
+
+const PLACE_ORDER: &str = "
+    mutation PlaceOrder($input: PlaceOrderInput!) {
+        placeOrder(input: $input) {
+            id
+            orderStatus
+            placedAt
+        }
+    }
+";
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires a Docker daemon"]
+async fn created_order_is_placed_and_its_order_created_event_published() {
+    let docker = Cli::default();
+    let harness = TestHarness::start(&docker).await;
+    let foreign_types = harness.seed_foreign_types(1000).await;
+    let shopping_cart_item_id = Uuid::new();
+    harness
+        .mock_shopping_cart(shopping_cart_item_id, foreign_types.product_variant_id, 2)
+        .await;
+    harness
+        .mock_inventory(foreign_types.product_variant_id, 10)
+        .await;
+    harness
+        .mock_no_discounts(foreign_types.product_variant_id)
+        .await;
+    harness.mock_shipment_fees(499).await;
+    harness.expect_publications(ORDER_CREATED_TOPIC, 1).await;
+
+    let created_order = create_order(&harness, &foreign_types, shopping_cart_item_id).await;
+    assert_eq!(created_order["orderStatus"], "PENDING");
+    assert_eq!(created_order["grandTotal"]["amount"], 2499);
+    assert!(harness
+        .published_events(ORDER_CREATED_TOPIC)
+        .await
+        .is_empty());
+
+    let order_id = created_order["id"].as_str().unwrap();
+    let placed_order = harness
+        .graphql(
+            PLACE_ORDER,
+            json!({ "input": { "id": order_id } }),
+            foreign_types.user_id,
+        )
+        .await["placeOrder"]
+        .clone();
+    assert_eq!(placed_order["id"], order_id);
+    assert_eq!(placed_order["orderStatus"], "PLACED");
+    assert!(!placed_order["placedAt"].is_null());
+
+    harness.dapr_sidecar.verify().await;
+    let published_events = harness.published_events(ORDER_CREATED_TOPIC).await;
+    assert_eq!(published_events[0]["id"], order_id);
+    assert_eq!(
+        published_events[0]["userId"],
+        foreign_types.user_id.to_string()
+    );
+    let stored_order = harness
+        .db_client
+        .collection::<Document>("orders")
+        .find_one(doc! {"_id": Uuid::parse_str(order_id).unwrap()}, None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored_order.get_bool("event_published"), Ok(true));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires a Docker daemon"]
+async fn concurrently_placed_order_is_placed_and_published_once() {
+    let docker = Cli::default();
+    let harness = TestHarness::start(&docker).await;
+    let foreign_types = harness.seed_foreign_types(1000).await;
+    let shopping_cart_item_id = Uuid::new();
+    harness
+        .mock_shopping_cart(shopping_cart_item_id, foreign_types.product_variant_id, 1)
+        .await;
+    harness
+        .mock_inventory(foreign_types.product_variant_id, 10)
+        .await;
+    harness
+        .mock_no_discounts(foreign_types.product_variant_id)
+        .await;
+    harness.mock_shipment_fees(499).await;
+    harness.expect_publications(ORDER_CREATED_TOPIC, 1).await;
+    let created_order = create_order(&harness, &foreign_types, shopping_cart_item_id).await;
+    let order_id = created_order["id"].as_str().unwrap();
+
+    let place_order_variables = json!({ "input": { "id": order_id } });
+    let (first_response, second_response) = tokio::join!(
+        harness.graphql_response(
+            PLACE_ORDER,
+            place_order_variables.clone(),
+            foreign_types.user_id
+        ),
+        harness.graphql_response(PLACE_ORDER, place_order_variables, foreign_types.user_id),
+    );
+    let successful_responses: Vec<_> = [first_response, second_response]
+        .into_iter()
+        .filter(|response| response.get("errors").is_none())
+        .collect();
+    assert_eq!(successful_responses.len(), 1);
+    assert_eq!(
+        successful_responses[0]["data"]["placeOrder"]["orderStatus"],
+        "PLACED"
+    );
+
+    harness.dapr_sidecar.verify().await;
+    let published_events = harness.published_events(ORDER_CREATED_TOPIC).await;
+    assert_eq!(published_events.len(), 1);
+    assert_eq!(published_events[0]["id"], order_id);
+}
+
+/// Creates a pending order of the single shopping cart item mocked for the seeded user and returns it.
+///
+/// * `harness` - Test harness running the order service.
+/// * `foreign_types` - Foreign types seeded for the order.
+/// * `shopping_cart_item_id` - UUID of the mocked shopping cart item.
+async fn create_order(
+    harness: &TestHarness<'_>,
+    foreign_types: &ForeignTypes,
+    shopping_cart_item_id: Uuid,
+) -> serde_json::Value {
+    let create_order_input = json!({
+        "userId": foreign_types.user_id.to_string(),
+        "orderItemInputs": [{
+            "shoppingCartItemId": shopping_cart_item_id.to_string(),
+            "shipmentMethodId": foreign_types.shipment_method_id.to_string(),
+            "couponIds": [],
+        }],
+        "shipmentAddressId": foreign_types.user_address_id.to_string(),
+        "invoiceAddressId": foreign_types.user_address_id.to_string(),
+        "paymentInformationId": foreign_types.payment_information_id.to_string(),
+    });
+    harness
+        .graphql(
+            CREATE_ORDER,
+            json!({ "input": create_order_input }),
+            foreign_types.user_id,
+        )
+        .await["createOrder"]
+        .clone()
+}